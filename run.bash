@@ -74,10 +74,17 @@ rustup target add wasm32-unknown-unknown || true
 cargo build --release --target wasm32-unknown-unknown
 
 echo ">>> Publishing and creating application..."
+# Deployment settings (ChessParameters as JSON): fees, allowed variants and time controls, hub chain
+CHESS_PARAMETERS=${CHESS_PARAMETERS:-'{}'}
+# Defaults for the creator chain (InstantiationArgument as JSON): display name, default time control,
+# auto-accept policy and spectator visibility
+CHESS_INSTANTIATION_ARGUMENT=${CHESS_INSTANTIATION_ARGUMENT:-'{}'}
 LINERA_APPLICATION_ID=$(linera --wait-for-outgoing-messages \
   publish-and-create \
   /build/target/wasm32-unknown-unknown/release/onchainchess_contract.wasm \
-  /build/target/wasm32-unknown-unknown/release/onchainchess_service.wasm)
+  /build/target/wasm32-unknown-unknown/release/onchainchess_service.wasm \
+  --json-parameters "$CHESS_PARAMETERS" \
+  --json-argument "$CHESS_INSTANTIATION_ARGUMENT")
 export VITE_LINERA_APPLICATION_ID=$LINERA_APPLICATION_ID
 
 echo ">>> Creating frontend .env file..."
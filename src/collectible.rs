@@ -0,0 +1,117 @@
+// Collectible records of finished games: minted by a player from their copy of the game,
+// held in a registry on the holder's chain and transferable to other chains.
+
+use async_graphql::SimpleObject;
+use linera_sdk::linera_base_types::AccountOwner;
+use serde::{Deserialize, Serialize};
+
+use crate::{Color, Game, PlayerInfo, Termination};
+
+// PGN movetext lines are kept under this width
+const PGN_LINE_WIDTH: usize = 79;
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct Collectible {
+    pub collectible_id: String, // Match ID and the minter's colour, so each player mints a game once
+    pub match_id: String,
+    pub minted_by: String, // Chain of the player who minted it
+    pub minted_at: String,
+    pub owner: Option<AccountOwner>, // Only this signer may transfer it; None lets the holder chain do so
+    pub players: Vec<PlayerInfo>,
+    pub result: String, // PGN result: "1-0", "0-1", "1/2-1/2" or "*"
+    pub termination: Option<Termination>,
+    pub pgn: String,
+    pub final_fen: String,
+    pub svg: String, // Final position
+}
+
+pub fn collectible_id(match_id: &str, color: Color) -> String {
+    match color {
+        Color::White => format!("{}-white", match_id),
+        Color::Black => format!("{}-black", match_id),
+    }
+}
+
+pub fn pgn_result(game: &Game) -> &'static str {
+    if game.is_aborted() {
+        return "*";
+    }
+    match game.winner_chain_id.as_deref().and_then(|w| game.color_of(w)) {
+        Some(Color::White) => "1-0",
+        Some(Color::Black) => "0-1",
+        None => "1/2-1/2",
+    }
+}
+
+// PGN of a game from the SAN of its moves, in order
+pub fn game_pgn(game: &Game, sans: &[String]) -> String {
+    let name_of = |color| {
+        game.player_with_color(color)
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| "?".to_string())
+    };
+    let date = game
+        .created_at
+        .parse::<u64>()
+        .map(pgn_date)
+        .unwrap_or_else(|_| "????.??.??".to_string());
+    let result = pgn_result(game);
+
+    let mut pgn = String::new();
+    for (tag, value) in [
+        ("Event", "OnChainChess game".to_string()),
+        ("Site", game.host_chain_id.clone()),
+        ("Date", date),
+        ("Round", "-".to_string()),
+        ("White", name_of(Color::White)),
+        ("Black", name_of(Color::Black)),
+        ("Result", result.to_string()),
+    ] {
+        pgn.push_str(&format!("[{} \"{}\"]\n", tag, value.replace('\\', "\\\\").replace('"', "\\\"")));
+    }
+    if let Some(termination) = game.termination {
+        pgn.push_str(&format!("[Termination \"{:?}\"]\n", termination));
+    }
+    pgn.push('\n');
+
+    let mut tokens = Vec::with_capacity(sans.len() * 3 / 2 + 1);
+    for (index, san) in sans.iter().enumerate() {
+        if index % 2 == 0 {
+            tokens.push(format!("{}.", index / 2 + 1));
+        }
+        tokens.push(san.clone());
+    }
+    tokens.push(result.to_string());
+
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() > PGN_LINE_WIDTH {
+            pgn.push_str(&line);
+            pgn.push('\n');
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&token);
+    }
+    pgn.push_str(&line);
+    pgn.push('\n');
+    pgn
+}
+
+// "YYYY.MM.DD" for a timestamp in microseconds since the Unix epoch
+pub fn pgn_date(micros: u64) -> String {
+    // Days to civil date (proleptic Gregorian calendar)
+    let days = (micros / 86_400_000_000) as i64 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
@@ -4,19 +4,40 @@ mod state;
 
 use self::state::ChessState;
 use linera_sdk::{
-    linera_base_types::{ChainId, WithContractAbi},
+    abis::fungible::{Account, FungibleOperation, FungibleTokenAbi},
+    linera_base_types::{
+        AccountOwner, Amount, ApplicationId, ApplicationPermissions, ChainId, ChainOwnership,
+        TimeoutConfig, WithContractAbi,
+    },
     views::{RootView, View},
     Contract, ContractRuntime,
 };
 use onchainchess::{
     ChessAbi, CrossChainMessage, Game, InstantiationArgument, MatchStatus, Operation, PlayerInfo,
-    ChessMove, Color, MoveRecord, ChessParameters, Square,
+    ChessMove, Color, MoveRecord, ChessParameters, Square, MatchSeries, SeriesGameResult,
+    Termination, TakebackRequest, DEFAULT_INACTIVITY_LIMIT_SECS, READY_TIMEOUT_SECS, Tournament, TournamentGameRef,
+    GameRecord, Clock, TournamentFormat, TournamentStatus, ChessEvent, GameUpdate, game_stream_name,
+    results_stream_name, MAX_RESULT_WATCHERS,
+    Notification, NotificationKind, MAX_NOTIFICATIONS, Divergence, DivergenceKind, MAX_DIVERGENCES,
+    MoveSequencer, SequenceOutcome, SequencedMove, Escrow, Stake, PrizePool, PoolDeposit,
+    DepositKind, TimeControl, Variant, AutoAcceptPolicy, PendingJoin, SpectatorVisibility,
+    ChessResponse, GameResult, ResultWatcher, Collectible, collectible, render,
+    PieceType, engine, ENGINE_PLAYER_ID, STARTING_FEN,
 };
-use shakmaty::{Chess, Position, Square as ShakSquare, Move};
+use shakmaty::{Chess, Position, Role, Square as ShakSquare, Move};
+use shakmaty::san::SanPlus;
+use shakmaty::uci::UciMove;
 use shakmaty::fen::Fen;
 
 linera_sdk::contract!(ChessContract);
 
+// Tokens moved from the host's chain to pay for the blocks of a shared game chain
+const GAME_CHAIN_BALANCE_TOKENS: u128 = 1;
+// Rounds in which either owner may propose a block on a shared game chain
+const GAME_CHAIN_MULTI_LEADER_ROUNDS: u32 = 10;
+// Fuel set aside per engine search node, so that a reply never runs the block out of fuel
+const ENGINE_FUEL_PER_NODE: u64 = 50_000;
+
 pub struct ChessContract {
     state: ChessState,
     runtime: ContractRuntime<Self>,
@@ -32,6 +53,992 @@ impl ChessContract {
         self.state.opponent_ready.set(false);
     }
 
+    // The chain of the player acting in this block. Players are identified by their signer:
+    // on a shared game chain the signer picks the player, and on a player's own chain only
+    // that player's signer may act for them (chains of unsigned players act as themselves)
+    fn acting_chain_id(&mut self) -> ChainId {
+        let chain_id = self.runtime.chain_id();
+        let signer = self.runtime.authenticated_signer();
+        let game = if let Some(game) = self.state.game.get() {
+            game
+        } else {
+            return chain_id;
+        };
+        if game.is_game_chain(&chain_id.to_string()) {
+            let signer = signer.expect("Operations on a game chain must be signed");
+            let player = game
+                .player_by_owner(&signer)
+                .expect("Signer is not a player in this game");
+            return player.chain_id.parse().expect("Invalid player chain ID");
+        }
+        if let (Some(game_chain_id), false) = (&game.game_chain_id, game.status == MatchStatus::Ended) {
+            panic!("This game is played on its shared chain {}", game_chain_id);
+        }
+        let owner = game
+            .players
+            .iter()
+            .find(|p| p.chain_id == chain_id.to_string())
+            .and_then(|p| p.owner);
+        if owner.is_some() && owner != signer {
+            panic!("Only the player's own signer can act for them");
+        }
+        chain_id
+    }
+
+    fn wager_token(&mut self) -> ApplicationId<FungibleTokenAbi> {
+        self.runtime
+            .application_parameters()
+            .wager_token
+            .expect("Wagers are not enabled for this application")
+            .with_abi::<FungibleTokenAbi>()
+    }
+
+    // The application's own account on `chain_id`, which holds stakes and prize pools
+    fn app_account(&mut self, chain_id: ChainId) -> Account {
+        Account {
+            chain_id,
+            owner: AccountOwner::from(self.runtime.application_id().forget_abi()),
+        }
+    }
+
+    // Move funds from the signer to the application's account on `chain_id`; returns the signer
+    fn deposit_to_app(&mut self, chain_id: ChainId, amount: Amount) -> AccountOwner {
+        let owner = self
+            .runtime
+            .authenticated_signer()
+            .expect("Payments require a signed operation");
+        let token = self.wager_token();
+        let target_account = self.app_account(chain_id);
+        self.runtime.call_application(
+            true,
+            token,
+            &FungibleOperation::Transfer { owner, amount, target_account },
+        );
+        owner
+    }
+
+    // Pay out of the application's account on this chain
+    fn pay_from_app(&mut self, amount: Amount, target_account: Account) {
+        let token = self.wager_token();
+        let this_chain = self.runtime.chain_id();
+        let owner = self.app_account(this_chain).owner;
+        self.runtime.call_application(
+            true,
+            token,
+            &FungibleOperation::Transfer { owner, amount, target_account },
+        );
+    }
+
+    // Move the host's stake into escrow on this chain
+    fn lock_stake(&mut self, match_id: String, amount: Amount) {
+        if self.state.escrow.get().is_some() {
+            panic!("Stakes of another game are still held in escrow");
+        }
+        let this_chain = self.runtime.chain_id();
+        let owner = self.deposit_to_app(this_chain, amount);
+        self.state.escrow.set(Some(Escrow {
+            match_id,
+            stakes: vec![Stake { chain_id: this_chain.to_string(), owner, amount }],
+        }));
+    }
+
+    // Turn down a join request, returning the stake it brought along
+    fn decline_join(&mut self, player_chain: ChainId, owner: Option<AccountOwner>, stake: Option<Amount>) {
+        if let (Some(amount), Some(owner)) = (stake, owner) {
+            self.pay_from_app(amount, Account { chain_id: player_chain, owner });
+        }
+        let host_chain_id = self.runtime.chain_id();
+        self.runtime
+            .send_message(player_chain, CrossChainMessage::JoinDeclined { host_chain_id });
+    }
+
+    // Settle the escrowed stakes once the game is over: the winner collects both, less the
+    // platform fee on the loser's stake; draws and aborted games refund each stake
+    fn settle_escrow(&mut self) {
+        let escrow = match self.state.escrow.get() {
+            Some(escrow) => escrow.clone(),
+            None => return,
+        };
+        let game = match self.state.game.get() {
+            Some(game) if game.status == MatchStatus::Ended && escrow.match_id == game.match_id => game.clone(),
+            _ => return,
+        };
+        let winner = game
+            .winner_chain_id
+            .as_deref()
+            .and_then(|winner| game.players.iter().find(|p| p.chain_id == winner));
+        let mut transfers = Vec::new();
+        match winner {
+            Some(winner) => {
+                let parameters = self.runtime.application_parameters();
+                let total: Amount = escrow.stakes.iter().fold(Amount::ZERO, |sum, s| sum.saturating_add(s.amount));
+                let fee = escrow
+                    .stakes
+                    .iter()
+                    .filter(|s| s.chain_id != winner.chain_id)
+                    .fold(Amount::ZERO, |sum, s| sum.saturating_add(parameters.fee_on(s.amount)));
+                if let (Some(account), true) = (self.fee_account(), fee > Amount::ZERO) {
+                    transfers.push((fee, account));
+                }
+                let winner_account = Account {
+                    chain_id: winner.chain_id.parse().expect("Invalid winner chain ID"),
+                    owner: winner.owner.expect("Staked games have signed players"),
+                };
+                transfers.push((total.saturating_sub(fee), winner_account));
+            }
+            None => {
+                for stake in &escrow.stakes {
+                    let account = Account {
+                        chain_id: stake.chain_id.parse().expect("Invalid player chain ID"),
+                        owner: stake.owner,
+                    };
+                    transfers.push((stake.amount, account));
+                }
+            }
+        }
+        for (amount, account) in transfers {
+            self.pay_from_app(amount, account);
+        }
+        self.state.escrow.set(None);
+        self.notify_about(NotificationKind::StakeReleased, Some(escrow.match_id), None);
+    }
+
+    fn fee_account(&mut self) -> Option<Account> {
+        self.runtime
+            .application_parameters()
+            .fee_recipient
+            .map(|recipient| Account {
+                chain_id: recipient.chain_id.parse().expect("Invalid fee recipient chain ID"),
+                owner: recipient.owner,
+            })
+    }
+
+    // Enforce the deployment's allowed variants and time controls
+    fn check_game_settings(&mut self, variant: Variant, time_control: Option<TimeControl>) {
+        let parameters = self.runtime.application_parameters();
+        if !parameters.allows_variant(variant) {
+            panic!("Variant {:?} is not allowed on this deployment", variant);
+        }
+        if !parameters.allows_time_control(time_control) {
+            panic!("Time control is not allowed on this deployment");
+        }
+    }
+
+    // The name to play under: the one given, or else the profile's display name
+    fn display_name(&self, name: String) -> String {
+        if !name.trim().is_empty() {
+            return name;
+        }
+        let display_name = &self.state.profile.get().display_name;
+        if display_name.is_empty() {
+            panic!("No name given and no display name in the profile");
+        }
+        display_name.clone()
+    }
+
+    // Send a game message to the opponent; on a shared game chain both players already see it
+    fn send_to_opponent(&mut self, opponent: ChainId, message: CrossChainMessage) {
+        let this_chain = self.runtime.chain_id().to_string();
+        let on_game_chain = self
+            .state
+            .game
+            .get()
+            .as_ref()
+            .map(|g| g.is_game_chain(&this_chain))
+            .unwrap_or(false);
+        if !on_game_chain {
+            self.runtime.send_message(opponent, message);
+        }
+    }
+
+    // Open a chain owned by both players and hand the game over to it
+    fn open_game_chain(&mut self, game: &mut Game) {
+        let owners = game
+            .players
+            .iter()
+            .map(|p| (p.owner.expect("Both players must sign to share a game chain"), 100))
+            .collect::<Vec<_>>();
+        let ownership = ChainOwnership::multiple(owners, GAME_CHAIN_MULTI_LEADER_ROUNDS, TimeoutConfig::default());
+        let permissions = ApplicationPermissions::new_single(self.runtime.application_id().forget_abi());
+        let game_chain = self.runtime.open_chain(
+            ownership,
+            permissions,
+            Amount::from_tokens(GAME_CHAIN_BALANCE_TOKENS),
+        );
+        game.game_chain_id = Some(game_chain.to_string());
+        self.runtime
+            .send_message(game_chain, CrossChainMessage::HostGame { game: game.clone() });
+    }
+
+    // Apply a move from the opponent's chain that continues our history; returns whether it applied
+    fn apply_opponent_move(&mut self, incoming: SequencedMove) -> bool {
+        let SequencedMove {
+            ply,
+            chess_move,
+            player_chain_id,
+            clock_remaining_micros,
+            position_hash,
+        } = incoming;
+        // Extract values before getting mutable borrow
+            let self_chain = self.runtime.chain_id().to_string();
+        let now = self.runtime.system_time().micros();
+        let timestamp = now.to_string();
+        
+        // Use get_mut() to modify game state through View system
+        let game = if let Some(game) = self.state.game.get_mut() {
+            game
+        } else {
+            return false; // Match not found, skip
+        };
+
+        // Check if game can be played (read-only check)
+        let can_play = game.status == MatchStatus::Active && game.players.len() == 2;
+        if !can_play {
+            return false;
+        }
+
+        // Determine opponent color
+        let is_host = game.host_chain_id == self_chain;
+        let opponent_color = if is_host {
+            Color::Black
+        } else {
+            Color::White
+        };
+
+        // Validate it's opponent's turn
+        if game.current_turn != opponent_color {
+            self.report_divergence(DivergenceKind::WrongTurn, ply, Some(position_hash), None);
+            return false;
+        }
+
+        // Reconstruct position from move history
+        let mut position = match Self::reconstruct_position_from_moves(&game.move_history) {
+            Ok(pos) => pos,
+            Err(_) => {
+                self.report_divergence(DivergenceKind::ReconstructFailed, ply, Some(position_hash), None);
+                return false;
+            }
+        };
+
+        // Validate and apply move using WASM
+        let shakmaty_move = match Self::chess_move_to_shakmaty(&chess_move, &position) {
+            Ok(mv) => mv,
+            Err(_) => {
+                self.report_divergence(DivergenceKind::IllegalMove, ply, Some(position_hash), None);
+                return false;
+            }
+        };
+
+        // Apply move
+        let san = SanPlus::from_move(position.clone(), shakmaty_move).to_string();
+        position = match position.play(shakmaty_move) {
+            Ok(new_pos) => new_pos,
+            Err(_) => {
+                self.report_divergence(DivergenceKind::IllegalMove, ply, Some(position_hash), None);
+                return false;
+            }
+        };
+        let local_hash = Self::position_hash(&position);
+
+        // Take over the mover's remaining time and start our own clock
+        let clock_remaining_micros = match (game.clock.as_mut(), clock_remaining_micros) {
+            (Some(clock), Some(reported)) => Some(clock.sync_after_move(opponent_color, reported, now)),
+            (_, reported) => reported,
+        };
+
+        // Compute FEN after move
+        let fen_after = Self::compute_fen(&position);
+
+        // Detect game end conditions and determine winner
+        if let Some((end_status, winner, termination)) = Self::detect_game_end(&position, opponent_color, game) {
+            game.status = end_status;
+            game.winner_chain_id = winner;
+            game.termination = Some(termination);
+        }
+
+        // Create move record with computed FEN
+        let move_number = (game.move_history.len() + 1) as u32;
+        let move_record = MoveRecord {
+            move_number,
+            chess_move: chess_move.clone(),
+            player_color: opponent_color,
+            timestamp: timestamp.clone(),
+            fen_after: fen_after.clone(),
+            clock_remaining_micros,
+        };
+
+        // Update game in-place through View system
+        game.move_history.push(move_record.clone());
+        game.current_turn = if game.current_turn == Color::White {
+            Color::Black
+        } else {
+            Color::White
+        };
+        game.last_move_at = Some(timestamp);
+        game.board = fen_after; // Update board FEN
+        game.takeback_request = None;
+        game.clear_declined_draw_offer(opponent_color);
+        let match_id = game.match_id.clone();
+        self.notify(NotificationKind::OpponentMoved, Some(player_chain_id));
+        self.publish(ChessEvent::MovePlayed { match_id, san, move_record });
+        if local_hash != position_hash {
+            self.report_divergence(DivergenceKind::HashMismatch, ply, Some(position_hash), Some(local_hash));
+        }
+        self.finalize_game();
+        true
+    }
+
+    // Apply buffered opponent moves that now continue our history
+    fn apply_buffered_moves(&mut self) {
+        loop {
+            let (match_id, expected_ply) = match self.state.game.get() {
+                Some(game) if game.status == MatchStatus::Active => {
+                    (game.match_id.clone(), game.move_history.len() as u32 + 1)
+                }
+                _ => return,
+            };
+            let next = match self.state.move_sequencer.get_mut() {
+                Some(sequencer) if sequencer.match_id == match_id => sequencer.take_next(expected_ply),
+                _ => None,
+            };
+            match next {
+                Some(incoming) => {
+                    if !self.apply_opponent_move(incoming) {
+                        return;
+                    }
+                }
+                None => return,
+            }
+        }
+    }
+
+    // Record that our copy of the game disagrees with the opponent's and resynchronise:
+    // the host pushes its copy, a guest asks the host for it
+    fn report_divergence(
+        &mut self,
+        kind: DivergenceKind,
+        ply: u32,
+        remote_hash: Option<u64>,
+        local_hash: Option<u64>,
+    ) {
+        let self_chain = self.runtime.chain_id().to_string();
+        let chain_id_for_message = self.runtime.chain_id();
+        let detected_at = self.runtime.system_time().micros().to_string();
+        let game = if let Some(game) = self.state.game.get() {
+            game.clone()
+        } else {
+            return;
+        };
+
+        let divergences = self.state.divergences.get_mut();
+        divergences.push(Divergence {
+            match_id: game.match_id.clone(),
+            ply,
+            local_ply: game.move_history.len() as u32,
+            kind,
+            remote_hash: remote_hash.map(|h| format!("{:016x}", h)),
+            local_hash: local_hash.map(|h| format!("{:016x}", h)),
+            detected_at,
+            resolved: false,
+        });
+        if divergences.len() > MAX_DIVERGENCES {
+            let excess = divergences.len() - MAX_DIVERGENCES;
+            divergences.drain(..excess);
+        }
+        self.request_resync(&game, &self_chain, chain_id_for_message);
+    }
+
+    fn request_resync(&mut self, game: &Game, self_chain: &str, chain_id_for_message: ChainId) {
+        if game.host_chain_id == self_chain {
+            let opponent_chain_id = game
+                .opponent_of(self_chain)
+                .and_then(|p| p.chain_id.parse().ok());
+            if let Some(opponent) = opponent_chain_id {
+                self.runtime
+                    .send_message(opponent, CrossChainMessage::GameSync { game: game.clone() });
+            }
+        } else {
+            let host: ChainId = game.host_chain_id.parse().expect("Invalid host chain ID");
+            self.runtime.send_message(
+                host,
+                CrossChainMessage::ResyncRequest {
+                    player_chain_id: chain_id_for_message,
+                    ply: game.move_history.len() as u32,
+                },
+            );
+        }
+    }
+
+    // Seat the guest; the game (and its clock) starts once both players are ready
+    fn accept_join(
+        &mut self,
+        player_chain_id: ChainId,
+        player_name: String,
+        owner: Option<AccountOwner>,
+        stake: Option<Amount>,
+    ) {
+        let now = self.runtime.system_time().micros();
+        self.decline_pending_joins(Some(&player_chain_id.to_string()));
+        // The guest's stake joins the host's in escrow
+        if let (Some(escrow), Some(amount), Some(owner)) = (self.state.escrow.get_mut(), stake, owner) {
+            escrow.stakes.push(Stake { chain_id: player_chain_id.to_string(), owner, amount });
+        }
+        let game = if let Some(game) = self.state.game.get_mut() {
+            game
+        } else {
+            panic!("Match not found");
+        };
+        game.players.push(PlayerInfo {
+            chain_id: player_chain_id.to_string(),
+            name: player_name,
+            owner,
+        });
+        game.status = MatchStatus::WaitingForReady;
+        game.ready_deadline_micros = Some(now + READY_TIMEOUT_SECS * 1_000_000);
+        if let Some(series) = game.series.as_mut() {
+            series.scores[1].chain_id = player_chain_id.to_string();
+        }
+        let mut game_for_message = game.clone();
+
+        // Reset local state
+        self.state.my_ready.set(false);
+        self.state.opponent_ready.set(false);
+
+        // From here on a shared game is played on its own chain; our copy points there
+        if game_for_message.shared_chain {
+            self.open_game_chain(&mut game_for_message);
+            self.state.game.set(Some(game_for_message.clone()));
+        }
+
+        self.runtime.send_message(
+            player_chain_id,
+            CrossChainMessage::InitialStateSync { game: game_for_message.clone() },
+        );
+        self.publish(ChessEvent::GameStarted { game: Box::new(game_for_message) });
+        self.notify(NotificationKind::PlayerJoined, Some(player_chain_id.to_string()));
+    }
+
+    // Whether the message being executed was sent by our opponent's chain, as it claims.
+    // Game-ending messages settle stakes, so nobody else may send them.
+    fn is_from_opponent(&mut self, player_chain_id: ChainId) -> bool {
+        let self_chain = self.runtime.chain_id().to_string();
+        if self.runtime.message_origin_chain_id() != Some(player_chain_id) {
+            return false;
+        }
+        self.state
+            .game
+            .get()
+            .as_ref()
+            .and_then(|g| g.opponent_of(&self_chain))
+            .is_some_and(|p| p.chain_id == player_chain_id.to_string())
+    }
+
+    // Turn down join requests still waiting for the host, except `keep`
+    fn decline_pending_joins(&mut self, keep: Option<&str>) {
+        let pending = std::mem::take(self.state.pending_joins.get_mut());
+        for request in pending {
+            if Some(request.player_chain_id.as_str()) == keep {
+                continue;
+            }
+            let player_chain: ChainId = request.player_chain_id.parse().expect("Invalid player chain ID");
+            self.decline_join(player_chain, request.owner, request.stake);
+        }
+    }
+
+    // Start a joined game (and its clock) once both players have confirmed they are ready
+    fn activate_if_ready(&mut self) {
+        if !*self.state.my_ready.get() || !*self.state.opponent_ready.get() {
+            return;
+        }
+        let now = self.runtime.system_time().micros();
+        let game = match self.state.game.get_mut() {
+            Some(game) if game.status == MatchStatus::WaitingForReady => game,
+            _ => return,
+        };
+        game.status = MatchStatus::Active;
+        game.started_at = Some(now.to_string());
+        game.ready_deadline_micros = None;
+        if let Some(clock) = game.clock.as_mut() {
+            clock.start(now);
+        }
+        let match_id = game.match_id.clone();
+        self.publish(ChessEvent::PlayersReady {
+            match_id,
+            started_at: now,
+        });
+    }
+
+    // Add a notification to the inbox, dropping the oldest ones beyond the limit
+    fn notify_about(
+        &mut self,
+        kind: NotificationKind,
+        match_id: Option<String>,
+        counterparty_chain_id: Option<String>,
+    ) {
+        let id = *self.state.next_notification_id.get();
+        self.state.next_notification_id.set(id + 1);
+        let timestamp = self.runtime.system_time().micros().to_string();
+        let notifications = self.state.notifications.get_mut();
+        notifications.push(Notification {
+            id,
+            kind,
+            message: kind.message().to_string(),
+            match_id,
+            counterparty_chain_id,
+            timestamp,
+            read: false,
+        });
+        if notifications.len() > MAX_NOTIFICATIONS {
+            let excess = notifications.len() - MAX_NOTIFICATIONS;
+            notifications.drain(..excess);
+        }
+    }
+
+    // Notification about the current game
+    fn notify(&mut self, kind: NotificationKind, counterparty_chain_id: Option<String>) {
+        let match_id = self.state.game.get().as_ref().map(|g| g.match_id.clone());
+        self.notify_about(kind, match_id, counterparty_chain_id);
+    }
+
+    // Log an update of the current game for clients, and publish it on the game's stream
+    // (the host's chain, or the shared game chain, is the single publisher)
+    fn publish(&mut self, event: ChessEvent) {
+        let self_chain = self.runtime.chain_id().to_string();
+        let timestamp = self.runtime.system_time().micros().to_string();
+        let game = match self.state.game.get() {
+            Some(game) if game.match_id == event.match_id() => game,
+            _ => return,
+        };
+        let on_game_chain = game.is_game_chain(&self_chain);
+        let is_publisher = game.host_chain_id == self_chain || on_game_chain;
+        // Spectators follow the host's stream, so a shared game chain relays its events to the
+        // players' chains, which publish them
+        let relay_to: Vec<ChainId> = if on_game_chain {
+            game.players
+                .iter()
+                .filter_map(|p| p.chain_id.parse().ok())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let (san, actor_chain_id) = match &event {
+            ChessEvent::MovePlayed { san, move_record, .. } => (
+                Some(san.clone()),
+                game.player_with_color(move_record.player_color).map(|p| p.chain_id.clone()),
+            ),
+            ChessEvent::DrawOffered { offered_by, .. } => (None, Some(offered_by.clone())),
+            ChessEvent::TakebackRequested { request, .. } => (None, Some(request.requested_by.clone())),
+            _ => (None, None),
+        };
+        let update = GameUpdate {
+            sequence: self.state.updates.count() as u64,
+            match_id: game.match_id.clone(),
+            kind: event.kind(),
+            status: game.status,
+            ply: game.move_history.len() as u32,
+            fen: game.board.clone(),
+            san,
+            actor_chain_id,
+            timestamp,
+        };
+        self.state.updates.push(update);
+        if is_publisher && game.spectators_allowed {
+            self.runtime.emit(game_stream_name(event.match_id()), &event);
+        }
+        for player_chain in relay_to {
+            self.runtime
+                .send_message(player_chain, CrossChainMessage::GameChainEvent { event: event.clone() });
+        }
+    }
+
+    // Mirror an event from a spectated game's stream
+    async fn apply_spectator_event(&mut self, event: ChessEvent) {
+        let match_id = event.match_id().to_string();
+        let ended = matches!(event, ChessEvent::GameEnded { .. });
+        let game = match event {
+            ChessEvent::GameStarted { game } => *game,
+            ChessEvent::ResultRecorded { .. } => return, // Not a game stream event
+            event => {
+                let mut game = if let Some(game) = self
+                    .state
+                    .spectated
+                    .get(&match_id)
+                    .await
+                    .expect("Failed to read spectated game")
+                {
+                    game
+                } else {
+                    return; // Not watching this game
+                };
+                game.apply_event(&event);
+                game
+            }
+        };
+        let result = game.result();
+        self.state
+            .spectated
+            .insert(&match_id, game)
+            .expect("Failed to store spectated game");
+        if ended {
+            self.record_result(result);
+        }
+    }
+
+    // Result of a match known to this chain: archived, current or spectated
+    async fn game_result(&self, match_id: &str) -> Option<GameResult> {
+        if let Some(result) = self
+            .state
+            .results
+            .get(match_id)
+            .await
+            .expect("Failed to read results")
+        {
+            return Some(result);
+        }
+        if let Some(game) = self.state.game.get().as_ref().filter(|g| g.match_id == match_id) {
+            return Some(game.result());
+        }
+        self.state
+            .spectated
+            .get(match_id)
+            .await
+            .expect("Failed to read spectated game")
+            .map(|game| game.result())
+    }
+
+    // Archive the result of an ended match and publish it for the applications waiting for it;
+    // they read it from the stream, so none of their code runs in this transaction
+    fn record_result(&mut self, result: GameResult) {
+        self.state
+            .result_watchers
+            .get_mut()
+            .retain(|w| w.match_id != result.match_id);
+        self.state
+            .results
+            .insert(&result.match_id, result.clone())
+            .expect("Failed to store result");
+        self.runtime
+            .emit(results_stream_name(), &ChessEvent::ResultRecorded { result });
+    }
+
+    // End-of-game bookkeeping, run once per match on this chain
+    fn finalize_game(&mut self) {
+        let game = if let Some(game) = self.state.game.get_mut() {
+            game
+        } else {
+            return;
+        };
+        if game.status != MatchStatus::Ended {
+            return;
+        }
+        // A takeback or draw offer left pending can no longer change the finished game
+        game.takeback_request = None;
+        game.draw_offered_by = None;
+        if self.state.finalized_match_id.get().as_deref() == Some(game.match_id.as_str()) {
+            return;
+        }
+
+        // A shared game chain hands the result to both players, who do the bookkeeping, and closes
+        let this_chain = self.runtime.chain_id().to_string();
+        if game.is_game_chain(&this_chain) {
+            let game = game.clone();
+            for player in &game.players {
+                let player_chain: ChainId = player.chain_id.parse().expect("Invalid player chain ID");
+                self.runtime
+                    .send_message(player_chain, CrossChainMessage::GameChainResult { game: game.clone() });
+            }
+            self.state.finalized_match_id.set(Some(game.match_id.clone()));
+            self.publish(ChessEvent::GameEnded {
+                match_id: game.match_id,
+                winner_chain_id: game.winner_chain_id,
+                termination: game.termination,
+            });
+            self.runtime.close_chain().expect("Failed to close the game chain");
+            return;
+        }
+
+        // Score the game in its series (if any); aborted games are unrated
+        let white_chain_id = game.player_with_color(Color::White).map(|p| p.chain_id.clone());
+        let black_chain_id = game.player_with_color(Color::Black).map(|p| p.chain_id.clone());
+        if let (false, Some(series), Some(white_chain_id), Some(black_chain_id)) =
+            (game.is_aborted(), game.series.as_mut(), white_chain_id, black_chain_id)
+        {
+            series.record(SeriesGameResult {
+                match_id: game.match_id.clone(),
+                white_chain_id,
+                black_chain_id,
+                winner_chain_id: game.winner_chain_id.clone(),
+                armageddon: game.armageddon,
+            });
+        }
+
+        // Count the game in our player's statistics; practice games against the engine are left out
+        if let (Some(outcome), Some(color), None) =
+            (game.outcome_for(&this_chain), game.color_of(&this_chain), game.engine_level)
+        {
+            self.state
+                .profile
+                .get_mut()
+                .stats
+                .record(color, outcome, game.variant(), game.time_category());
+        }
+
+        // Both players report tournament games back to the organizer, who keeps the first
+        // report and drops the other as a duplicate (aborted games count as draws)
+        if let Some(tournament) = game.tournament.as_ref() {
+            let organizer: ChainId = tournament
+                .organizer_chain_id
+                .parse()
+                .expect("Invalid organizer chain ID");
+            self.runtime.send_message(
+                organizer,
+                CrossChainMessage::TournamentGameResult {
+                    tournament_id: tournament.tournament_id.clone(),
+                    round: tournament.round,
+                    board: tournament.board,
+                    game_number: tournament.game_number,
+                    match_id: game.match_id.clone(),
+                    winner_chain_id: game.winner_chain_id.clone(),
+                    white_berserk: game.clock.as_ref().map(|c| c.white_berserk).unwrap_or(false),
+                    black_berserk: game.clock.as_ref().map(|c| c.black_berserk).unwrap_or(false),
+                },
+            );
+        }
+
+        let match_id = game.match_id.clone();
+        let result = game.result();
+        let event = ChessEvent::GameEnded {
+            match_id: match_id.clone(),
+            winner_chain_id: game.winner_chain_id.clone(),
+            termination: game.termination,
+        };
+        self.state.finalized_match_id.set(Some(match_id));
+        self.publish(event);
+        // Requests to join the ended match can no longer be accepted
+        self.decline_pending_joins(None);
+        self.settle_escrow();
+        self.record_result(result);
+    }
+
+    // Pair the next round (or finish) and start a game for every board
+    fn advance_tournament(&mut self, tournament: &mut Tournament) {
+        let now = self.runtime.system_time().micros();
+        let next_round = match tournament.advance(now) {
+            Ok(round) => round.cloned(),
+            Err(e) => panic!("Cannot advance tournament: {}", e),
+        };
+        let round = if let Some(round) = next_round {
+            round
+        } else {
+            self.distribute_prizes(tournament);
+            return; // Tournament finished
+        };
+
+        // Byes are scored without a game
+        for pairing in round.pairings.iter() {
+            self.start_tournament_game(tournament, round.number, pairing.board);
+        }
+    }
+
+    // Pay the prize pool out by final place, once; with nobody to pay, deposits are refunded
+    fn distribute_prizes(&mut self, tournament: &mut Tournament) {
+        if tournament.prize_pool.distributed || tournament.status != TournamentStatus::Finished {
+            return;
+        }
+        tournament.prize_pool.distributed = true;
+        if tournament.prize_pool.total() == Amount::ZERO {
+            return;
+        }
+        // Nothing is charged when the pool is refunded for lack of players
+        if !tournament.players.is_empty() {
+            tournament.prize_pool.platform_fee =
+                self.runtime.application_parameters().fee_on(tournament.prize_pool.entry_fees);
+        }
+        let payouts = tournament.prize_pool.compute_payouts(&tournament.final_ranking());
+        let mut transfers: Vec<(Amount, Account)> = if payouts.is_empty() {
+            Self::deposit_refunds(&tournament.prize_pool)
+        } else {
+            payouts
+                .iter()
+                .map(|p| (p.amount, Self::prize_account(&p.chain_id, p.owner)))
+                .collect()
+        };
+        if let Some(account) = self.fee_account() {
+            if tournament.prize_pool.platform_fee > Amount::ZERO {
+                transfers.push((tournament.prize_pool.platform_fee, account));
+            }
+        }
+        tournament.prize_pool.payouts = payouts;
+        for (amount, account) in transfers {
+            self.pay_from_app(amount, account);
+        }
+    }
+
+    // Return every entry fee and sponsorship of a cancelled tournament, once
+    fn refund_prize_pool(&mut self, tournament: &mut Tournament) {
+        if tournament.prize_pool.distributed || tournament.status != TournamentStatus::Cancelled {
+            return;
+        }
+        tournament.prize_pool.distributed = true;
+        for (amount, account) in Self::deposit_refunds(&tournament.prize_pool) {
+            self.pay_from_app(amount, account);
+        }
+    }
+
+    fn deposit_refunds(prize_pool: &PrizePool) -> Vec<(Amount, Account)> {
+        prize_pool
+            .deposits
+            .iter()
+            .map(|d| (d.amount, Self::prize_account(&d.chain_id, Some(d.owner))))
+            .collect()
+    }
+
+    fn prize_account(chain_id: &str, owner: Option<AccountOwner>) -> Account {
+        Account {
+            chain_id: chain_id.parse().expect("Invalid player chain ID"),
+            owner: owner.unwrap_or(AccountOwner::CHAIN),
+        }
+    }
+
+    // Ask White of the board's next game to host it (no-op if the board needs no more games)
+    fn start_tournament_game(&mut self, tournament: &Tournament, round: u32, board: u32) {
+        let scheduled = if let Some(scheduled) = tournament.next_game(round, board) {
+            scheduled
+        } else {
+            return;
+        };
+        let white = tournament.player(&scheduled.white_chain_id).expect("Unknown player");
+        let black = tournament.player(&scheduled.black_chain_id).expect("Unknown player");
+        let white_chain: ChainId = white.chain_id.parse().expect("Invalid player chain ID");
+        self.runtime.send_message(
+            white_chain,
+            CrossChainMessage::TournamentGameStart {
+                tournament: TournamentGameRef {
+                    tournament_id: tournament.tournament_id.clone(),
+                    organizer_chain_id: tournament.organizer_chain_id.clone(),
+                    round,
+                    board,
+                    game_number: scheduled.game_number,
+                    arena: tournament.arena.is_some(),
+                },
+                white: PlayerInfo {
+                    chain_id: white.chain_id.clone(),
+                    name: white.name.clone(),
+                    owner: white.owner,
+                },
+                black: PlayerInfo {
+                    chain_id: black.chain_id.clone(),
+                    name: black.name.clone(),
+                    owner: black.owner,
+                },
+                time_control: scheduled.time_control,
+                armageddon: scheduled.armageddon,
+            },
+        );
+    }
+
+    // After a recorded game: start the board's next mini-match game, or pair the next round
+    // once the current one is complete (arena players are re-paired as soon as their game ends)
+    fn continue_tournament(&mut self, tournament: &mut Tournament, round: u32, board: u32, record: GameRecord) {
+        if record == GameRecord::NextGame {
+            self.start_tournament_game(tournament, round, board);
+            self.save_tournament(tournament);
+            return;
+        }
+        let round_complete = tournament
+            .current_round()
+            .map(|r| r.number == round && r.is_complete())
+            .unwrap_or(false);
+        if round_complete || tournament.arena.is_some() {
+            self.advance_tournament(tournament);
+        }
+        self.save_tournament(tournament);
+    }
+
+    async fn load_tournament(&self, tournament_id: &str) -> Option<Tournament> {
+        self.state
+            .tournaments
+            .get(tournament_id)
+            .await
+            .expect("Failed to read tournament")
+    }
+
+    fn save_tournament(&mut self, tournament: &Tournament) {
+        self.state
+            .tournaments
+            .insert(&tournament.tournament_id, tournament.clone())
+            .expect("Failed to store tournament");
+    }
+
+    // Start the rematch on this chain as the new host (colours swap: the old guest plays White).
+    // Like a joined game, it waits for both players to be ready.
+    fn start_rematch(&mut self) {
+        let self_chain = self.runtime.chain_id().to_string();
+        let now = self.runtime.system_time().micros();
+        let timestamp = now.to_string();
+
+        let previous = if let Some(game) = self.state.game.get() {
+            game.clone()
+        } else {
+            panic!("Match not found");
+        };
+        let me = previous
+            .players
+            .iter()
+            .find(|p| p.chain_id == self_chain)
+            .cloned()
+            .expect("Not a player in this match");
+        let opponent = previous
+            .opponent_of(&self_chain)
+            .cloned()
+            .expect("Opponent not found");
+
+        let clock = previous.clock.as_ref().map(|c| Clock::new(c.time_control));
+
+        // A decided series is not carried into the rematch
+        let series = previous.series.filter(|series| !series.finished);
+        let armageddon = series.as_ref().map(|s| s.needs_armageddon()).unwrap_or(false);
+
+        let game = Game {
+            match_id: timestamp.clone(),
+            host_chain_id: self_chain,
+            status: MatchStatus::WaitingForReady,
+            players: vec![me, opponent.clone()],
+            current_turn: Color::White,
+            board: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
+            move_history: Vec::new(),
+            created_at: timestamp,
+            started_at: None,
+            ready_deadline_micros: Some(now + READY_TIMEOUT_SECS * 1_000_000),
+            last_move_at: None,
+            winner_chain_id: None,
+            termination: None,
+            rated: previous.rated,
+            inactivity_limit_secs: previous.inactivity_limit_secs,
+            takeback_request: None,
+            clock,
+            series,
+            tournament: None,
+            armageddon,
+            rematch_offered_by: None,
+            draw_offered_by: None,
+            // Rematches are played on the players' own chains
+            shared_chain: false,
+            game_chain_id: None,
+            wager: None, // Stakes are settled per game and not carried over
+            spectators_allowed: previous.spectators_allowed,
+            engine_level: None,
+        };
+        self.state.game.set(Some(game.clone()));
+        self.reset_local_state();
+        self.notify(NotificationKind::RematchStarted, Some(opponent.chain_id.clone()));
+        self.publish(ChessEvent::GameStarted { game: Box::new(game.clone()) });
+
+        let opponent_chain: ChainId = opponent.chain_id.parse().expect("Invalid opponent chain ID");
+        self.runtime
+            .send_message(opponent_chain, CrossChainMessage::InitialStateSync { game });
+    }
+
     // Convert our Square to shakmaty Square
     fn square_to_shakmaty(sq: &Square) -> Result<ShakSquare, String> {
         if sq.file > 7 || sq.rank > 7 {
@@ -44,112 +1051,202 @@ impl ChessContract {
         Ok(ShakSquare::from_coords(file, rank))
     }
 
-    // Convert our ChessMove to shakmaty Move
+    // Convert our PieceType to a shakmaty Role
+    fn piece_type_to_role(piece_type: PieceType) -> Role {
+        match piece_type {
+            PieceType::Pawn => Role::Pawn,
+            PieceType::Knight => Role::Knight,
+            PieceType::Bishop => Role::Bishop,
+            PieceType::Rook => Role::Rook,
+            PieceType::Queen => Role::Queen,
+            PieceType::King => Role::King,
+        }
+    }
+
+    // Convert our ChessMove to shakmaty Move. Castling is the king's two-square step, and a
+    // promotion must name its piece.
     fn chess_move_to_shakmaty(
         chess_move: &ChessMove,
         position: &Chess,
     ) -> Result<Move, String> {
-        let from = Self::square_to_shakmaty(&chess_move.from)?;
-        let to = Self::square_to_shakmaty(&chess_move.to)?;
-
-        // Find the move in legal moves
-        let legal_moves = position.legal_moves();
-        for legal_move in legal_moves {
-            // legal_move.from() returns Option<Square>, legal_move.to() returns Square
-            if legal_move.from() == Some(from) && legal_move.to() == to {
-                // For promotion moves, check if target is on promotion rank (rank 0 or 7)
-                // In shakmaty, we can check the rank index
-                let target_rank = to.rank();
-                let rank_index = target_rank as u32;
-                
-                if let Some(_promo) = chess_move.promotion {
-                    // Promotion move - must be to rank 0 (First) or 7 (Eighth)
-                    if rank_index == 0 || rank_index == 7 {
-                        return Ok(legal_move);
-                    }
-                } else {
-                    // Non-promotion move - must not be to promotion rank
-                    if rank_index != 0 && rank_index != 7 {
-                        return Ok(legal_move);
-                    }
+        let uci = UciMove::Normal {
+            from: Self::square_to_shakmaty(&chess_move.from)?,
+            to: Self::square_to_shakmaty(&chess_move.to)?,
+            promotion: chess_move.promotion.map(Self::piece_type_to_role),
+        };
+        uci.to_move(position).map_err(|_| "Move is not legal".to_string())
+    }
+
+    // In a game against the engine, search and play its answer to the move just made
+    fn play_engine_reply(&mut self) {
+        let (level, move_history) = match self.state.game.get() {
+            Some(game) if game.status == MatchStatus::Active && game.current_turn == Color::Black => {
+                match game.engine_level {
+                    Some(level) => (level, game.move_history.clone()),
+                    None => return,
                 }
             }
+            _ => return,
+        };
+        let position = match Self::reconstruct_position_from_moves(&move_history) {
+            Ok(position) => position,
+            Err(e) => panic!("Failed to reconstruct position: {}", e),
+        };
+        let node_limit = level
+            .node_limit()
+            .min(self.runtime.remaining_fuel() / ENGINE_FUEL_PER_NODE);
+        let engine_move = engine::search(&position, level.depth(), node_limit)
+            .best_move
+            .expect("Engine has no legal move in an active game");
+        let san = SanPlus::from_move(position.clone(), engine_move).to_string();
+        let position = match position.play(engine_move) {
+            Ok(position) => position,
+            Err(e) => panic!("Failed to apply engine move: {:?}", e),
+        };
+        let fen_after = Self::compute_fen(&position);
+        let timestamp = self.runtime.system_time().micros().to_string();
+
+        let game = if let Some(game) = self.state.game.get_mut() {
+            game
+        } else {
+            return;
+        };
+        if let Some((end_status, winner, termination)) = Self::detect_game_end(&position, Color::Black, game) {
+            game.status = end_status;
+            game.winner_chain_id = winner;
+            game.termination = Some(termination);
         }
-        Err("Move is not legal".to_string())
+        let move_record = MoveRecord {
+            move_number: (game.move_history.len() + 1) as u32,
+            chess_move: engine::to_chess_move(engine_move),
+            player_color: Color::Black,
+            timestamp: timestamp.clone(),
+            fen_after: fen_after.clone(),
+            clock_remaining_micros: None,
+        };
+        game.move_history.push(move_record.clone());
+        game.current_turn = Color::White;
+        game.last_move_at = Some(timestamp);
+        game.board = fen_after;
+        game.clear_declined_draw_offer(Color::Black);
+        let match_id = game.match_id.clone();
+        self.publish(ChessEvent::MovePlayed { match_id, san, move_record });
+        self.finalize_game();
+    }
+
+    // SAN of every move in the history, replayed from the initial position
+    fn move_sans(move_history: &[MoveRecord]) -> Result<Vec<String>, String> {
+        let mut position = Chess::default();
+        let mut sans = Vec::with_capacity(move_history.len());
+        for move_record in move_history {
+            let shakmaty_move = Self::chess_move_to_shakmaty(&move_record.chess_move, &position)?;
+            sans.push(SanPlus::from_move(position.clone(), shakmaty_move).to_string());
+            position = position
+                .play(shakmaty_move)
+                .map_err(|e| format!("Failed to apply move: {:?}", e))?;
+        }
+        Ok(sans)
     }
 
     // Reconstruct position from move history
     fn reconstruct_position_from_moves(move_history: &[MoveRecord]) -> Result<Chess, String> {
         let mut position = Chess::default();
-        
         for move_record in move_history {
-            let from = Self::square_to_shakmaty(&move_record.chess_move.from)?;
-            let to = Self::square_to_shakmaty(&move_record.chess_move.to)?;
-            
-            // Find matching legal move
-            let legal_moves = position.legal_moves();
-            let mut found_move = None;
-            for legal_move in legal_moves {
-                // legal_move.from() returns Option<Square>, legal_move.to() returns Square
-                if legal_move.from() == Some(from) && legal_move.to() == to {
-                    // Check if this matches a promotion move
-                    let target_rank = to.rank();
-                    let rank_index = target_rank as u32;
-                    
-                    if move_record.chess_move.promotion.is_some() {
-                        // If promotion is specified, check if move is to promotion rank (0 or 7)
-                        if rank_index == 0 || rank_index == 7 {
-                            found_move = Some(legal_move);
-                            break;
-                        }
-                    } else {
-                        // Not a promotion move
-                        if rank_index != 0 && rank_index != 7 {
-                            found_move = Some(legal_move);
-                            break;
-                        }
-                    }
-                }
-            }
-            
-            let chess_move = found_move.ok_or_else(|| "Invalid move in history".to_string())?;
+            let chess_move = Self::chess_move_to_shakmaty(&move_record.chess_move, &position)
+                .map_err(|_| "Invalid move in history".to_string())?;
             position = position.play(chess_move).map_err(|e| format!("Failed to apply move: {:?}", e))?;
         }
-        
         Ok(position)
     }
 
+    // Undo the last `plies` half-moves and restore turn, board and move clock from the history
+    fn apply_takeback(game: &mut Game, plies: u32, at_ply: u32, now: u64) -> Result<(), String> {
+        if game.move_history.len() as u32 != at_ply {
+            return Err("Takeback is stale".to_string());
+        }
+        if plies == 0 || plies > at_ply {
+            return Err("Not enough moves to take back".to_string());
+        }
+
+        game.move_history.truncate((at_ply - plies) as usize);
+        let position = Self::reconstruct_position_from_moves(&game.move_history)?;
+        game.board = Self::compute_fen(&position);
+        game.current_turn = match position.turn() {
+            shakmaty::Color::White => Color::White,
+            shakmaty::Color::Black => Color::Black,
+        };
+        // The player to move gets a fresh stretch before they count as inactive
+        game.last_move_at = Some(now.to_string());
+        game.takeback_request = None;
+        if let Some(clock) = game.clock.as_mut() {
+            clock.restore(&game.move_history, now);
+        }
+        Ok(())
+    }
+
+    async fn record_abandonment(&mut self, chain_id: String) {
+        // A shared game chain closes after the game, so the players' chains keep the count
+        let this_chain = self.runtime.chain_id().to_string();
+        let game_chain_players = match self.state.game.get() {
+            Some(game) if game.is_game_chain(&this_chain) => Some(game.players.clone()),
+            _ => None,
+        };
+        if let Some(players) = game_chain_players {
+            for player in players {
+                let player_chain: ChainId = player.chain_id.parse().expect("Invalid player chain ID");
+                self.runtime.send_message(
+                    player_chain,
+                    CrossChainMessage::GameChainAbandonment { chain_id: chain_id.clone() },
+                );
+            }
+            return;
+        }
+        let count = self
+            .state
+            .abandonments
+            .get(&chain_id)
+            .await
+            .expect("Failed to read abandonments")
+            .unwrap_or(0);
+        self.state
+            .abandonments
+            .insert(&chain_id, count + 1)
+            .expect("Failed to record abandonment");
+    }
+
     // Compute FEN from position
     fn compute_fen(position: &Chess) -> String {
         use shakmaty::EnPassantMode;
         Fen::from_position(position, EnPassantMode::Always).to_string()
     }
 
+    // Zobrist hash of a position, exchanged with each move to detect diverging copies
+    fn position_hash(position: &Chess) -> u64 {
+        use shakmaty::{zobrist::Zobrist64, EnPassantMode};
+        position.zobrist_hash::<Zobrist64>(EnPassantMode::Legal).0
+    }
+
     // Detect game end conditions and determine winner
-    // Returns (status, winner_chain_id) where winner_chain_id is None for draws
+    // Returns (status, winner_chain_id, termination) where winner_chain_id is None for draws
     fn detect_game_end(
         position: &Chess,
-        _player_color: Color,
+        player_color: Color,
         game: &Game,
-        self_chain: &str,
-    ) -> Option<(MatchStatus, Option<String>)> {
+    ) -> Option<(MatchStatus, Option<String>, Termination)> {
         if position.is_checkmate() {
-            // If current player is in checkmate, opponent wins
-            // Find opponent's chain ID
-            let opponent_chain_id = game
-                .players
-                .iter()
-                .find(|p| p.chain_id != self_chain)
+            // The side to move is mated, so the player who just moved wins
+            let winner_chain_id = game
+                .player_with_color(player_color)
                 .map(|p| p.chain_id.clone());
-            return Some((MatchStatus::Ended, opponent_chain_id));
+            return Some((MatchStatus::Ended, winner_chain_id, Termination::Checkmate));
         }
         if position.is_stalemate() {
             // Stalemate is a draw, no winner
-            return Some((MatchStatus::Ended, None));
+            return Some((MatchStatus::Ended, None, Termination::Stalemate));
         }
         if position.is_insufficient_material() {
             // Insufficient material is a draw, no winner
-            return Some((MatchStatus::Ended, None));
+            return Some((MatchStatus::Ended, None, Termination::InsufficientMaterial));
         }
         // Check for threefold repetition and 50-move rule would require move history
         None
@@ -160,7 +1257,7 @@ impl Contract for ChessContract {
     type Message = CrossChainMessage;
     type InstantiationArgument = InstantiationArgument;
     type Parameters = ChessParameters;
-    type EventValue = ();
+    type EventValue = ChessEvent;
 
     async fn load(runtime: ContractRuntime<Self>) -> Self {
         let state = ChessState::load(runtime.root_view_storage_context())
@@ -169,59 +1266,198 @@ impl Contract for ChessContract {
         ChessContract { state, runtime }
     }
 
-    async fn instantiate(&mut self, _argument: InstantiationArgument) {
+    async fn instantiate(&mut self, argument: InstantiationArgument) {
+        if let Err(e) = self.runtime.application_parameters().validate() {
+            panic!("Invalid application parameters: {}", e);
+        }
         self.state.game.set(None);
         self.reset_local_state();
-        self.state.last_notification.set(None);
+
+        // Set the creator chain up to play right away
+        if let Err(e) = self.state.profile.get_mut().update(argument.display_name, None, None, None, None) {
+            panic!("Invalid profile: {}", e);
+        }
+        if argument.settings.default_time_control.is_some() {
+            self.check_game_settings(Variant::Standard, argument.settings.default_time_control);
+        }
+        self.state.settings.set(argument.settings);
     }
 
-    async fn execute_operation(&mut self, operation: Operation) -> () {
+    async fn execute_operation(&mut self, operation: Operation) -> ChessResponse {
+        // The engine only answers moves
+        let needs_opponent = matches!(
+            operation,
+            Operation::RequestTakeback
+                | Operation::OfferDraw
+                | Operation::OfferRematch
+                | Operation::ClaimAbandonment
+                | Operation::Berserk
+        );
+        if needs_opponent && self.state.game.get().as_ref().is_some_and(|g| g.engine_level.is_some()) {
+            panic!("Not available against the engine");
+        }
+
         match operation {
-            Operation::CreateMatch { host_name } => {
+            Operation::CreateMatch {
+                host_name,
+                series,
+                rated,
+                inactivity_limit_secs,
+                time_control,
+                shared_chain,
+                wager,
+            } => {
                 let chain_id = self.runtime.chain_id().to_string();
+                let owner = self.runtime.authenticated_signer();
+                if shared_chain && owner.is_none() {
+                    panic!("A shared game chain needs a signed CreateMatch");
+                }
+                if self.state.escrow.get().is_some() {
+                    panic!("Cancel or finish the staked match hosted here first");
+                }
+                let settings = self.state.settings.get().clone();
+                let time_control = time_control.or(settings.default_time_control);
+                self.check_game_settings(Variant::Standard, time_control);
+                if series.as_ref().is_some_and(|s| s.armageddon_tiebreak) {
+                    self.check_game_settings(Variant::Armageddon, time_control);
+                }
                 let match_id = self.runtime.system_time().micros().to_string();
                 let initial_board = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string();
                 
+                // The guest's score entry is filled in when they join
+                let series = series.map(|format| MatchSeries::new(match_id.clone(), format, &chain_id, ""));
+
                 let game = Game {
                     match_id,
                     host_chain_id: chain_id.clone(),
                     status: MatchStatus::WaitingForPlayer,
                     players: vec![PlayerInfo {
                         chain_id,
-                        name: host_name,
+                        name: self.display_name(host_name),
+                        owner,
                     }],
                     current_turn: Color::White,
                     board: initial_board,
                     move_history: Vec::new(),
                     created_at: self.runtime.system_time().micros().to_string(),
+                    started_at: None,
+                    ready_deadline_micros: None,
                     last_move_at: None,
                     winner_chain_id: None,
+                    termination: None,
+                    rated,
+                    inactivity_limit_secs: inactivity_limit_secs.unwrap_or(DEFAULT_INACTIVITY_LIMIT_SECS),
+                    takeback_request: None,
+                    clock: time_control.map(Clock::new),
+                    series,
+                    tournament: None,
+                    armageddon: false,
+                    rematch_offered_by: None,
+                    draw_offered_by: None,
+                    shared_chain,
+                    game_chain_id: None,
+                    wager,
+                    spectators_allowed: settings.spectator_visibility == SpectatorVisibility::Public,
+                    engine_level: None,
                 };
+                if let Some(amount) = wager {
+                    self.lock_stake(game.match_id.clone(), amount);
+                }
+                // Requests left over from the match this one replaces
+                self.decline_pending_joins(None);
                 self.state.game.set(Some(game));
                 self.reset_local_state();
-                self.state.last_notification.set(None);
+            }
+
+            Operation::PlayEngine { player_name, level } => {
+                let chain_id = self.runtime.chain_id().to_string();
+                if self.state.game.get().as_ref().is_some_and(|g| g.status != MatchStatus::Ended) {
+                    panic!("Finish the current game first");
+                }
+                if self.state.escrow.get().is_some() {
+                    panic!("Cancel or finish the staked match hosted here first");
+                }
+                let now = self.runtime.system_time().micros();
+                let settings = self.state.settings.get().clone();
+                let game = Game {
+                    match_id: now.to_string(),
+                    host_chain_id: chain_id.clone(),
+                    status: MatchStatus::Active,
+                    players: vec![
+                        PlayerInfo {
+                            chain_id,
+                            name: self.display_name(player_name),
+                            owner: self.runtime.authenticated_signer(),
+                        },
+                        PlayerInfo {
+                            chain_id: ENGINE_PLAYER_ID.to_string(),
+                            name: format!("Engine ({:?})", level),
+                            owner: None,
+                        },
+                    ],
+                    current_turn: Color::White,
+                    board: STARTING_FEN.to_string(),
+                    move_history: Vec::new(),
+                    created_at: now.to_string(),
+                    started_at: Some(now.to_string()),
+                    ready_deadline_micros: None,
+                    last_move_at: None,
+                    winner_chain_id: None,
+                    termination: None,
+                    rated: false,
+                    inactivity_limit_secs: DEFAULT_INACTIVITY_LIMIT_SECS,
+                    takeback_request: None,
+                    clock: None,
+                    series: None,
+                    tournament: None,
+                    armageddon: false,
+                    rematch_offered_by: None,
+                    draw_offered_by: None,
+                    shared_chain: false,
+                    game_chain_id: None,
+                    wager: None,
+                    spectators_allowed: settings.spectator_visibility == SpectatorVisibility::Public,
+                    engine_level: Some(level),
+                };
+                self.state.game.set(Some(game.clone()));
+                self.reset_local_state();
+                self.publish(ChessEvent::GameStarted { game: Box::new(game) });
             }
 
             Operation::JoinMatch {
                 host_chain_id,
                 player_name,
+                wager,
             } => {
                 let target_chain: ChainId = host_chain_id.parse().expect("Invalid host chain ID");
                 let player_chain_id = self.runtime.chain_id();
+                let owner = self.runtime.authenticated_signer();
+                let player_name = self.display_name(player_name);
+                if self.state.escrow.get().is_some() {
+                    panic!("Cancel or finish the staked match hosted here first");
+                }
+                // The stake goes straight into escrow on the host's chain, which refunds it
+                // if the request is turned down
+                if let Some(amount) = wager {
+                    self.deposit_to_app(target_chain, amount);
+                }
                 self.runtime.send_message(
                     target_chain,
                     CrossChainMessage::JoinRequest {
                         player_chain_id,
                         player_name,
+                        owner,
+                        stake: wager,
                     },
                 );
             }
 
             Operation::MakeMove { chess_move } => {
                 // Extract values before getting mutable borrow
-                    let self_chain = self.runtime.chain_id().to_string();
-                let chain_id_for_message = self.runtime.chain_id();
-                let timestamp = self.runtime.system_time().micros().to_string();
+                let chain_id_for_message = self.acting_chain_id();
+                let self_chain = chain_id_for_message.to_string();
+                let now = self.runtime.system_time().micros();
+                let timestamp = now.to_string();
                 
                 // Use get_mut() to modify game state through View system
                 let game = if let Some(game) = self.state.game.get_mut() {
@@ -262,18 +1498,30 @@ impl Contract for ChessContract {
                 };
 
                 // Apply move
+                let san = SanPlus::from_move(position.clone(), shakmaty_move).to_string();
                 position = match position.play(shakmaty_move) {
                     Ok(new_pos) => new_pos,
                     Err(e) => panic!("Failed to apply move: {:?}", e),
                 };
 
-                // Compute FEN after move
+                // Stop our clock; a flagged player can only lose on time
+                let clock_remaining_micros = match game.clock.as_mut() {
+                    Some(clock) => match clock.punch(player_color, now) {
+                        Some(remaining) => Some(remaining),
+                        None => panic!("Out of time"),
+                    },
+                    None => None,
+                };
+
+                // Compute FEN and hash after move
                 let fen_after = Self::compute_fen(&position);
+                let position_hash = Self::position_hash(&position);
 
                 // Detect game end conditions and determine winner
-                if let Some((end_status, winner)) = Self::detect_game_end(&position, player_color, game, &self_chain) {
+                if let Some((end_status, winner, termination)) = Self::detect_game_end(&position, player_color, game) {
                     game.status = end_status;
                     game.winner_chain_id = winner;
+                    game.termination = Some(termination);
                 }
 
                 // Create move record with computed FEN
@@ -284,10 +1532,11 @@ impl Contract for ChessContract {
                     player_color,
                     timestamp: timestamp.clone(),
                     fen_after: fen_after.clone(),
+                    clock_remaining_micros,
                 };
 
                 // Update game in-place through View system
-                game.move_history.push(move_record);
+                game.move_history.push(move_record.clone());
                 game.current_turn = if game.current_turn == Color::White {
                     Color::Black
                 } else {
@@ -295,6 +1544,9 @@ impl Contract for ChessContract {
                 };
                 game.last_move_at = Some(timestamp);
                 game.board = fen_after; // Update board FEN
+                game.takeback_request = None;
+                game.clear_declined_draw_offer(player_color);
+                let match_id = game.match_id.clone();
 
                 // Get opponent chain ID before sending message
                 let opponent_chain_id = game.players
@@ -304,11 +1556,96 @@ impl Contract for ChessContract {
 
                 // Send move to opponent via cross-chain message
                 if let Some(opponent) = opponent_chain_id {
-                    self.runtime.send_message(
+                    self.send_to_opponent(
                         opponent,
                         CrossChainMessage::MoveSync {
                             chess_move,
                             player_chain_id: chain_id_for_message,
+                            clock_remaining_micros,
+                            ply: move_number,
+                            position_hash,
+                        },
+                    );
+                }
+                self.publish(ChessEvent::MovePlayed { match_id, san, move_record });
+                self.finalize_game();
+                self.play_engine_reply();
+                self.apply_buffered_moves();
+            }
+
+            Operation::ClaimTimeout => {
+                let chain_id_for_message = self.acting_chain_id();
+                let self_chain = chain_id_for_message.to_string();
+                let now = self.runtime.system_time().micros();
+
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
+                } else {
+                    panic!("Match not found");
+                };
+                if game.status != MatchStatus::Active {
+                    panic!("Match not active");
+                }
+                let my_color = game.color_of(&self_chain).expect("Not a player in this match");
+                if game.current_turn == my_color {
+                    panic!("Cannot claim timeout on your own turn");
+                }
+                let clock = game.clock.as_ref().expect("Game has no clock");
+                if clock.remaining(game.current_turn, game.current_turn, now) > 0 {
+                    panic!("Opponent still has time");
+                }
+
+                game.status = MatchStatus::Ended;
+                game.termination = Some(Termination::Timeout);
+                game.winner_chain_id = Some(self_chain.clone());
+
+                let opponent_chain_id = game
+                    .opponent_of(&self_chain)
+                    .and_then(|p| p.chain_id.parse().ok());
+                if let Some(opponent) = opponent_chain_id {
+                    self.send_to_opponent(
+                        opponent,
+                        CrossChainMessage::TimeoutNotice {
+                            player_chain_id: chain_id_for_message,
+                        },
+                    );
+                }
+                self.finalize_game();
+            }
+
+            Operation::Berserk => {
+                let chain_id_for_message = self.acting_chain_id();
+                let self_chain = chain_id_for_message.to_string();
+
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
+                } else {
+                    panic!("Match not found");
+                };
+                if game.status != MatchStatus::Active {
+                    panic!("Match not active");
+                }
+                if !game.tournament.as_ref().map(|t| t.arena).unwrap_or(false) {
+                    panic!("Berserk is only available in arena games");
+                }
+                let my_color = game.color_of(&self_chain).expect("Not a player in this match");
+                if game.move_history.iter().any(|m| m.player_color == my_color) {
+                    panic!("Berserk is only possible before your first move");
+                }
+                let clock = game.clock.as_mut().expect("Game has no clock");
+                if clock.is_berserk(my_color) {
+                    panic!("Already berserk");
+                }
+                clock.berserk(my_color);
+
+                let opponent_chain_id = game
+                    .opponent_of(&self_chain)
+                    .and_then(|p| p.chain_id.parse().ok());
+                if let Some(opponent) = opponent_chain_id {
+                    self.send_to_opponent(
+                        opponent,
+                        CrossChainMessage::BerserkNotice {
+                            player_chain_id: chain_id_for_message,
                         },
                     );
                 }
@@ -316,8 +1653,8 @@ impl Contract for ChessContract {
 
             Operation::ResignMatch => {
                 // Extract values before getting mutable borrow
-                    let self_chain = self.runtime.chain_id().to_string();
-                let chain_id_for_message = self.runtime.chain_id();
+                let chain_id_for_message = self.acting_chain_id();
+                let self_chain = chain_id_for_message.to_string();
                 
                 // Use get_mut() to modify game state through View system
                 let game = if let Some(game) = self.state.game.get_mut() {
@@ -325,8 +1662,12 @@ impl Contract for ChessContract {
                 } else {
                     panic!("Match not found");
                 };
+                if game.status != MatchStatus::Active && game.status != MatchStatus::WaitingForReady {
+                    panic!("Match not active");
+                }
 
                 game.status = MatchStatus::Ended;
+                game.termination = Some(Termination::Resignation);
                 
                 // Determine winner based on who resigned
                 let is_host = game.host_chain_id == self_chain;
@@ -348,19 +1689,20 @@ impl Contract for ChessContract {
 
                 // Notify opponent
                 if let Some(opponent) = opponent_chain_id {
-                    self.runtime.send_message(
+                    self.send_to_opponent(
                         opponent,
                         CrossChainMessage::ResignNotice {
                             player_chain_id: chain_id_for_message,
                         },
                     );
                 }
+                self.finalize_game();
             }
 
             Operation::EndGame { status } => {
                 // Extract values before getting mutable borrow
-                    let self_chain = self.runtime.chain_id().to_string();
-                let chain_id_for_message = self.runtime.chain_id();
+                let chain_id_for_message = self.acting_chain_id();
+                let self_chain = chain_id_for_message.to_string();
                 
                 // Use get_mut() to modify game state through View system
                 let game = if let Some(game) = self.state.game.get_mut() {
@@ -369,6 +1711,13 @@ impl Contract for ChessContract {
                     panic!("Match not found");
                 };
 
+                if game.status != MatchStatus::Active {
+                    panic!("Match not active");
+                }
+                // These games are scored, so they only end by the rules
+                if game.wager.is_some() || game.rated || game.tournament.is_some() || game.series.is_some() {
+                    panic!("Staked, rated, tournament and series games only end by the rules");
+                }
                 game.status = status;
                 
                 // Determine winner if game ended
@@ -385,7 +1734,7 @@ impl Contract for ChessContract {
 
                 // Notify opponent
                 if let Some(opponent) = opponent_chain_id {
-                    self.runtime.send_message(
+                    self.send_to_opponent(
                         opponent,
                         CrossChainMessage::GameEndNotice {
                             player_chain_id: chain_id_for_message,
@@ -393,183 +1742,1651 @@ impl Contract for ChessContract {
                         },
                     );
                 }
+                self.finalize_game();
             }
-        }
-    }
 
-    async fn execute_message(&mut self, message: Self::Message) {
-        match message {
-            CrossChainMessage::JoinRequest {
-                player_chain_id,
-                player_name,
-            } => {
-                // Extract values before getting mutable borrow
-                    let self_chain = self.runtime.chain_id().to_string();
-                
-                // Use get_mut() to modify game state through View system
+            Operation::CancelMatch => {
+                let self_chain = self.acting_chain_id().to_string();
+
                 let game = if let Some(game) = self.state.game.get_mut() {
                     game
                 } else {
                     panic!("Match not found");
                 };
-
-                // Check if host (read-only check)
-                let is_host = game.host_chain_id == self_chain;
-                if !is_host {
-                    panic!("Only host can accept joins");
+                if game.host_chain_id != self_chain {
+                    panic!("Only host can cancel the match");
                 }
                 if game.status != MatchStatus::WaitingForPlayer {
-                    panic!("Match not joinable");
+                    panic!("Only unjoined matches can be cancelled");
                 }
-                if game.players.len() >= 2 {
-                    panic!("Match full");
-                }
-
-                game.players.push(PlayerInfo {
-                    chain_id: player_chain_id.to_string(),
-                    name: player_name,
-                });
-                game.status = MatchStatus::Active;
-                
-                // Reset local state and set notification
-                self.state.my_ready.set(false);
-                self.state.opponent_ready.set(false);
-                self.state.last_notification.set(Some("Player joined".to_string()));
-                
-                // Need to clone game for the message since we can't move it
-                let game_for_message = game.clone();
-                self.runtime.send_message(player_chain_id, CrossChainMessage::InitialStateSync { game: game_for_message });
-            }
 
-            CrossChainMessage::InitialStateSync { game } => {
-                self.state.game.set(Some(game));
-                self.reset_local_state();
-                self.state.last_notification.set(Some("Match ready".to_string()));
+                // Nobody joined yet, so there is no opponent to notify
+                game.status = MatchStatus::Ended;
+                game.termination = Some(Termination::Aborted);
+                game.winner_chain_id = None;
+                self.finalize_game();
             }
 
-            CrossChainMessage::GameSync { game } => {
-                self.state.game.set(Some(game));
-                self.reset_local_state();
-            }
+            Operation::AbortGame => {
+                let chain_id_for_message = self.acting_chain_id();
+                let self_chain = chain_id_for_message.to_string();
 
-            CrossChainMessage::MoveSync {
-                chess_move,
-                player_chain_id: _,
-            } => {
-                // Extract values before getting mutable borrow
-                    let self_chain = self.runtime.chain_id().to_string();
-                let timestamp = self.runtime.system_time().micros().to_string();
-                
-                // Use get_mut() to modify game state through View system
                 let game = if let Some(game) = self.state.game.get_mut() {
                     game
                 } else {
-                    return; // Match not found, skip
+                    panic!("Match not found");
                 };
+                if game.status != MatchStatus::Active && game.status != MatchStatus::WaitingForReady {
+                    panic!("Match not active");
+                }
+                if game.move_history.len() >= 2 {
+                    panic!("Game can only be aborted before both sides have moved");
+                }
 
-                // Check if game can be played (read-only check)
-                let can_play = game.status == MatchStatus::Active && game.players.len() == 2;
-                if !can_play {
-                    return;
+                game.status = MatchStatus::Ended;
+                game.termination = Some(Termination::Aborted);
+                game.winner_chain_id = None;
+
+                let opponent_chain_id = game
+                    .opponent_of(&self_chain)
+                    .and_then(|p| p.chain_id.parse().ok());
+                if let Some(opponent) = opponent_chain_id {
+                    self.send_to_opponent(
+                        opponent,
+                        CrossChainMessage::AbortNotice {
+                            player_chain_id: chain_id_for_message,
+                        },
+                    );
                 }
+                self.finalize_game();
+            }
 
-                // Determine opponent color
-                let is_host = game.host_chain_id == self_chain;
-                let opponent_color = if is_host {
-                    Color::Black
+            Operation::RequestTakeback => {
+                let chain_id_for_message = self.acting_chain_id();
+                let self_chain = chain_id_for_message.to_string();
+
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
                 } else {
-                    Color::White
+                    panic!("Match not found");
                 };
-
-                // Validate it's opponent's turn
-                if game.current_turn != opponent_color {
-                    return;
+                if game.status != MatchStatus::Active {
+                    panic!("Match not active");
+                }
+                if game.rated || game.tournament.is_some() {
+                    panic!("Takebacks are not allowed in rated or tournament games");
                 }
 
-                // Reconstruct position from move history
-                let mut position = match Self::reconstruct_position_from_moves(&game.move_history) {
-                    Ok(pos) => pos,
-                    Err(_) => return, // Invalid position, skip move
+                // Take back our own last move, plus the opponent's reply if they already answered
+                let my_color = game.color_of(&self_chain).expect("Not a player in this match");
+                let at_ply = game.move_history.len() as u32;
+                let my_last_move = game
+                    .move_history
+                    .iter()
+                    .rposition(|m| m.player_color == my_color)
+                    .expect("No move to take back");
+                let plies = at_ply - my_last_move as u32;
+
+                let request = TakebackRequest {
+                    requested_by: self_chain.clone(),
+                    plies,
+                    at_ply,
+                };
+                game.takeback_request = Some(request.clone());
+                let event = ChessEvent::TakebackRequested {
+                    match_id: game.match_id.clone(),
+                    request,
                 };
 
-                // Validate and apply move using WASM
-                let shakmaty_move = match Self::chess_move_to_shakmaty(&chess_move, &position) {
-                    Ok(mv) => mv,
-                    Err(_) => return, // Invalid move, skip
+                let opponent_chain_id = game
+                    .opponent_of(&self_chain)
+                    .and_then(|p| p.chain_id.parse().ok());
+                if let Some(opponent) = opponent_chain_id {
+                    self.send_to_opponent(
+                        opponent,
+                        CrossChainMessage::TakebackRequested {
+                            player_chain_id: chain_id_for_message,
+                            plies,
+                            at_ply,
+                        },
+                    );
+                }
+                self.publish(event);
+            }
+
+            Operation::AcceptTakeback => {
+                let chain_id_for_message = self.acting_chain_id();
+                let self_chain = chain_id_for_message.to_string();
+                let now = self.runtime.system_time().micros();
+
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
+                } else {
+                    panic!("Match not found");
+                };
+                if game.status != MatchStatus::Active {
+                    panic!("Match not active");
+                }
+                let request = game.takeback_request.clone().expect("No takeback requested");
+                if request.requested_by == self_chain {
+                    panic!("Cannot accept your own takeback request");
+                }
+                if let Err(e) = Self::apply_takeback(game, request.plies, request.at_ply, now) {
+                    panic!("Takeback failed: {}", e);
+                }
+                let event = ChessEvent::TakebackApplied {
+                    match_id: game.match_id.clone(),
+                    moves_left: game.move_history.len() as u32,
                 };
+                self.state.move_sequencer.set(None);
 
-                // Apply move
-                position = match position.play(shakmaty_move) {
-                    Ok(new_pos) => new_pos,
-                    Err(_) => return, // Failed to apply, skip
+                let opponent: ChainId = request
+                    .requested_by
+                    .parse()
+                    .expect("Invalid opponent chain ID");
+                self.send_to_opponent(
+                    opponent,
+                    CrossChainMessage::TakebackAccepted {
+                        player_chain_id: chain_id_for_message,
+                        plies: request.plies,
+                        at_ply: request.at_ply,
+                    },
+                );
+                self.publish(event);
+            }
+
+            Operation::ClaimAbandonment => {
+                let chain_id_for_message = self.acting_chain_id();
+                let self_chain = chain_id_for_message.to_string();
+                let this_chain = self.runtime.chain_id().to_string();
+                let now = self.runtime.system_time().micros();
+
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
+                } else {
+                    panic!("Match not found");
+                };
+                let my_color = game.color_of(&self_chain).expect("Not a player in this match");
+                let opponent_to_blame = match game.status {
+                    // Ready timeout: either side may claim once the deadline has passed; the
+                    // opponent is only blamed if we confirmed and they did not
+                    MatchStatus::WaitingForReady => {
+                        if now < game.ready_deadline_micros.unwrap_or(0) {
+                            panic!("Players still have time to get ready");
+                        }
+                        let (mine, theirs) = if game.is_game_chain(&this_chain) && game.host_chain_id != self_chain {
+                            (*self.state.opponent_ready.get(), *self.state.my_ready.get())
+                        } else {
+                            (*self.state.my_ready.get(), *self.state.opponent_ready.get())
+                        };
+                        mine && !theirs
+                    }
+                    // Only the player waiting for the silent opponent can claim
+                    MatchStatus::Active => {
+                        if game.current_turn == my_color {
+                            panic!("Cannot claim abandonment on your own turn");
+                        }
+                        let limit_micros = game.inactivity_limit_secs.saturating_mul(1_000_000);
+                        if now.saturating_sub(game.last_activity_micros()) < limit_micros {
+                            panic!("Opponent is still within the inactivity limit");
+                        }
+                        true
+                    }
+                    _ => panic!("Match not active"),
                 };
 
-                // Compute FEN after move
-                let fen_after = Self::compute_fen(&position);
+                let opponent_chain_id = game
+                    .opponent_of(&self_chain)
+                    .map(|p| p.chain_id.clone())
+                    .expect("Opponent not found");
+                let opponent: ChainId = opponent_chain_id.parse().expect("Invalid opponent chain ID");
+                if !opponent_to_blame {
+                    // Nobody confirmed in time: the handshake simply expires into an abort
+                    game.status = MatchStatus::Ended;
+                    game.termination = Some(Termination::Aborted);
+                    game.winner_chain_id = None;
+                    self.send_to_opponent(
+                        opponent,
+                        CrossChainMessage::AbortNotice {
+                            player_chain_id: chain_id_for_message,
+                        },
+                    );
+                    self.finalize_game();
+                    return ChessResponse::Ok;
+                }
 
-                // Detect game end conditions and determine winner
-                if let Some((end_status, winner)) = Self::detect_game_end(&position, opponent_color, game, &self_chain) {
-                    game.status = end_status;
-                    game.winner_chain_id = winner;
+                // Nothing to win if the game never really started
+                let aborted = game.move_history.len() < 2;
+                game.status = MatchStatus::Ended;
+                if aborted {
+                    game.termination = Some(Termination::Aborted);
+                    game.winner_chain_id = None;
+                } else {
+                    game.termination = Some(Termination::Abandonment);
+                    game.winner_chain_id = Some(self_chain.clone());
                 }
 
-                // Create move record with computed FEN
-                let move_number = (game.move_history.len() + 1) as u32;
-                let move_record = MoveRecord {
-                    move_number,
-                    chess_move: chess_move.clone(),
-                    player_color: opponent_color,
-                    timestamp: timestamp.clone(),
-                    fen_after: fen_after.clone(),
+                self.send_to_opponent(
+                    opponent,
+                    CrossChainMessage::AbandonmentClaimed {
+                        player_chain_id: chain_id_for_message,
+                        aborted,
+                    },
+                );
+                self.record_abandonment(opponent_chain_id).await;
+                self.finalize_game();
+            }
+
+            Operation::RequestResync => {
+                let chain_id_for_message = self.acting_chain_id();
+                let self_chain = chain_id_for_message.to_string();
+                let this_chain = self.runtime.chain_id().to_string();
+                let game = if let Some(game) = self.state.game.get() {
+                    game.clone()
+                } else {
+                    panic!("Match not found");
                 };
+                if game.color_of(&self_chain).is_none() {
+                    panic!("Not a player in this match");
+                }
+                if game.is_game_chain(&this_chain) {
+                    panic!("A shared game chain holds the only copy of the game");
+                }
+                self.request_resync(&game, &self_chain, chain_id_for_message);
+            }
 
-                // Update game in-place through View system
-                game.move_history.push(move_record);
-                game.current_turn = if game.current_turn == Color::White {
-                    Color::Black
+            Operation::SetReady => {
+                let chain_id_for_message = self.acting_chain_id();
+                let self_chain = chain_id_for_message.to_string();
+                let this_chain = self.runtime.chain_id().to_string();
+
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
                 } else {
-                    Color::White
+                    panic!("Match not found");
                 };
-                game.last_move_at = Some(timestamp);
-                game.board = fen_after; // Update board FEN
+                if game.status != MatchStatus::WaitingForReady {
+                    panic!("Match is not waiting for players to get ready");
+                }
+                // On a shared game chain `my_ready` belongs to the host and `opponent_ready` to the guest
+                let my_ready = if game.is_game_chain(&this_chain) && game.host_chain_id != self_chain {
+                    &mut self.state.opponent_ready
+                } else {
+                    &mut self.state.my_ready
+                };
+                if *my_ready.get() {
+                    panic!("Already ready");
+                }
+                my_ready.set(true);
+                let match_id = game.match_id.clone();
+                let opponent_chain_id = game
+                    .opponent_of(&self_chain)
+                    .and_then(|p| p.chain_id.parse().ok());
+
+                if let Some(opponent) = opponent_chain_id {
+                    self.send_to_opponent(
+                        opponent,
+                        CrossChainMessage::ReadyNotice {
+                            player_chain_id: chain_id_for_message,
+                            match_id,
+                        },
+                    );
+                }
+                self.activate_if_ready();
             }
 
-            CrossChainMessage::ResignNotice { player_chain_id: _ } => {
-                // Use get_mut() to modify game state through View system
+            Operation::OfferDraw => {
+                let chain_id_for_message = self.acting_chain_id();
+                let self_chain = chain_id_for_message.to_string();
+
                 let game = if let Some(game) = self.state.game.get_mut() {
                     game
                 } else {
-                    return; // Match not found, skip
+                    panic!("Match not found");
+                };
+                if game.status != MatchStatus::Active {
+                    panic!("Match not active");
+                }
+                game.color_of(&self_chain).expect("Not a player in this match");
+                if game.draw_offered_by.is_some() {
+                    panic!("A draw offer is already pending");
+                }
+                game.draw_offered_by = Some(self_chain.clone());
+                let event = ChessEvent::DrawOffered {
+                    match_id: game.match_id.clone(),
+                    offered_by: self_chain.clone(),
                 };
 
-                game.status = MatchStatus::Ended;
-                // Winner is the one who didn't resign
-                    let self_chain = self.runtime.chain_id().to_string();
-                game.winner_chain_id = Some(self_chain);
-                self.state.last_notification.set(Some("Opponent resigned".to_string()));
+                let opponent_chain_id = game
+                    .opponent_of(&self_chain)
+                    .and_then(|p| p.chain_id.parse().ok());
+                if let Some(opponent) = opponent_chain_id {
+                    self.send_to_opponent(
+                        opponent,
+                        CrossChainMessage::DrawOffer {
+                            player_chain_id: chain_id_for_message,
+                        },
+                    );
+                }
+                self.publish(event);
             }
 
-            CrossChainMessage::GameEndNotice {
-                player_chain_id: _,
-                status,
-            } => {
-                // Use get_mut() to modify game state through View system
+            Operation::AcceptDraw => {
+                let chain_id_for_message = self.acting_chain_id();
+                let self_chain = chain_id_for_message.to_string();
+
                 let game = if let Some(game) = self.state.game.get_mut() {
                     game
                 } else {
-                    return; // Match not found, skip
+                    panic!("Match not found");
                 };
+                if game.status != MatchStatus::Active {
+                    panic!("Match not active");
+                }
+                let offered_by = game.draw_offered_by.clone().expect("No draw offered");
+                if offered_by == self_chain {
+                    panic!("Cannot accept your own draw offer");
+                }
 
-                game.status = status;
-                self.state.last_notification.set(Some("Game ended".to_string()));
+                game.status = MatchStatus::Ended;
+                game.termination = Some(Termination::Agreement);
+                game.winner_chain_id = None;
+                game.draw_offered_by = None;
+
+                let opponent: ChainId = offered_by.parse().expect("Invalid opponent chain ID");
+                self.send_to_opponent(
+                    opponent,
+                    CrossChainMessage::DrawAccepted {
+                        player_chain_id: chain_id_for_message,
+                    },
+                );
+                self.finalize_game();
             }
-        }
-    }
 
-    async fn process_streams(
-        &mut self,
-        _streams: Vec<linera_sdk::linera_base_types::StreamUpdate>,
-    ) {
+            Operation::MarkNotificationsRead { ids } => {
+                for notification in self.state.notifications.get_mut().iter_mut() {
+                    if ids.as_ref().map(|ids| ids.contains(&notification.id)).unwrap_or(true) {
+                        notification.read = true;
+                    }
+                }
+            }
+
+            Operation::AcceptJoin { player_chain_id } => {
+                let request = self
+                    .state
+                    .pending_joins
+                    .get()
+                    .iter()
+                    .find(|p| p.player_chain_id == player_chain_id)
+                    .cloned()
+                    .expect("No such join request");
+                let game = match self.state.game.get() {
+                    Some(game) if game.status == MatchStatus::WaitingForPlayer => game,
+                    _ => panic!("Match not joinable"),
+                };
+                // The request may predate the current match
+                if request.stake != game.wager {
+                    panic!("Join request stake does not match the wager");
+                }
+                if game.shared_chain && request.owner.is_none() {
+                    panic!("Cannot co-own a game chain without a signer");
+                }
+                let player_chain: ChainId = player_chain_id.parse().expect("Invalid player chain ID");
+                self.accept_join(player_chain, request.player_name, request.owner, request.stake);
+            }
+
+            Operation::DeclineJoin { player_chain_id } => {
+                let pending = self.state.pending_joins.get_mut();
+                let index = pending
+                    .iter()
+                    .position(|p| p.player_chain_id == player_chain_id)
+                    .expect("No such join request");
+                let request = pending.remove(index);
+                let player_chain: ChainId = player_chain_id.parse().expect("Invalid player chain ID");
+                self.decline_join(player_chain, request.owner, request.stake);
+            }
+
+            Operation::UpdateSettings { settings } => {
+                if settings.default_time_control.is_some() {
+                    self.check_game_settings(Variant::Standard, settings.default_time_control);
+                }
+                // Nobody picks among waiting requests any more: they have to ask again
+                if settings.auto_accept == AutoAcceptPolicy::Everyone {
+                    self.decline_pending_joins(None);
+                }
+                self.state.settings.set(settings);
+            }
+
+            Operation::UpdateProfile {
+                display_name,
+                avatar_hash,
+                country,
+                bio,
+                preferred_time_controls,
+            } => {
+                let profile = self.state.profile.get_mut();
+                if let Err(e) = profile.update(display_name, avatar_hash, country, bio, preferred_time_controls) {
+                    panic!("Invalid profile: {}", e);
+                }
+            }
+
+            Operation::WatchGame {
+                host_chain_id,
+                match_id,
+            } => {
+                let host_chain: ChainId = host_chain_id.parse().expect("Invalid host chain ID");
+                let application_id = self.runtime.application_id().forget_abi();
+                self.runtime
+                    .subscribe_to_events(host_chain, application_id, game_stream_name(&match_id));
+            }
+
+            Operation::MintCollectible => {
+                let player_chain = self.acting_chain_id().to_string();
+                let game = self.state.game.get().clone().expect("No game to mint");
+                if game.status != MatchStatus::Ended || game.is_aborted() {
+                    panic!("Only finished games can be minted");
+                }
+                let color = game.color_of(&player_chain).expect("Only the game's players can mint it");
+                let collectible_id = collectible::collectible_id(&game.match_id, color);
+                if self.state.minted_collectibles.get().contains(&collectible_id) {
+                    panic!("Game already minted");
+                }
+                let sans = match Self::move_sans(&game.move_history) {
+                    Ok(sans) => sans,
+                    Err(e) => panic!("Cannot replay the game: {}", e),
+                };
+                let options = render::SvgOptions {
+                    last_move: game.move_history.last().map(|m| (m.chess_move.from, m.chess_move.to)),
+                    ..Default::default()
+                };
+                let svg = render::board_svg(&game.board, &options).expect("Invalid final position");
+                let collectible = Collectible {
+                    collectible_id: collectible_id.clone(),
+                    match_id: game.match_id.clone(),
+                    minted_by: player_chain,
+                    minted_at: self.runtime.system_time().micros().to_string(),
+                    owner: self.runtime.authenticated_signer(),
+                    players: game.players.clone(),
+                    result: collectible::pgn_result(&game).to_string(),
+                    termination: game.termination,
+                    pgn: collectible::game_pgn(&game, &sans),
+                    final_fen: game.board.clone(),
+                    svg,
+                };
+                self.state.minted_collectibles.get_mut().push(collectible_id.clone());
+                self.state
+                    .collectibles
+                    .insert(&collectible_id, collectible)
+                    .expect("Failed to store collectible");
+            }
+
+            Operation::TransferCollectible {
+                collectible_id,
+                recipient_chain_id,
+                recipient_owner,
+            } => {
+                let collectible = self
+                    .state
+                    .collectibles
+                    .get(&collectible_id)
+                    .await
+                    .expect("Failed to read collectibles")
+                    .expect("No such collectible");
+                if collectible.owner.is_some() && collectible.owner != self.runtime.authenticated_signer() {
+                    panic!("Only the collectible's owner can transfer it");
+                }
+                let recipient: ChainId = recipient_chain_id.parse().expect("Invalid recipient chain ID");
+                self.state
+                    .collectibles
+                    .remove(&collectible_id)
+                    .expect("Failed to remove collectible");
+                self.runtime
+                    .prepare_message(CrossChainMessage::CollectibleTransfer {
+                        collectible,
+                        recipient_owner,
+                    })
+                    .with_tracking()
+                    .send_to(recipient);
+            }
+
+            Operation::QueryResult { match_id } => {
+                return ChessResponse::Result(self.game_result(&match_id).await);
+            }
+
+            Operation::WatchResult {
+                host_chain_id,
+                match_id,
+            } => {
+                let application_id = self
+                    .runtime
+                    .authenticated_caller_id()
+                    .expect("Only applications can watch results");
+                if let Some(result) = self.game_result(&match_id).await {
+                    if result.status == MatchStatus::Ended {
+                        return ChessResponse::Result(Some(result));
+                    }
+                }
+                let watchers = self.state.result_watchers.get_mut();
+                if !watchers
+                    .iter()
+                    .any(|w| w.match_id == match_id && w.application_id == application_id)
+                {
+                    if watchers.len() >= MAX_RESULT_WATCHERS {
+                        panic!("Too many results are being watched on this chain");
+                    }
+                    watchers.push(ResultWatcher {
+                        match_id: match_id.clone(),
+                        application_id,
+                    });
+                }
+
+                // Follow games that are neither played nor already watched here
+                let is_local = self.state.game.get().as_ref().is_some_and(|g| g.match_id == match_id);
+                let this_chain = self.runtime.chain_id().to_string();
+                if let (false, Some(host_chain_id)) = (is_local, host_chain_id) {
+                    if host_chain_id != this_chain {
+                        let host_chain: ChainId = host_chain_id.parse().expect("Invalid host chain ID");
+                        let application_id = self.runtime.application_id().forget_abi();
+                        self.runtime
+                            .subscribe_to_events(host_chain, application_id, game_stream_name(&match_id));
+                    }
+                }
+                return ChessResponse::Watching;
+            }
+
+            Operation::UnwatchGame {
+                host_chain_id,
+                match_id,
+            } => {
+                let host_chain: ChainId = host_chain_id.parse().expect("Invalid host chain ID");
+                let application_id = self.runtime.application_id().forget_abi();
+                self.runtime
+                    .unsubscribe_from_events(host_chain, application_id, game_stream_name(&match_id));
+                self.state
+                    .spectated
+                    .remove(&match_id)
+                    .expect("Failed to remove spectated game");
+            }
+
+            Operation::CreateTournament {
+                name,
+                format,
+                rounds,
+                time_control,
+                duration_minutes,
+                mini_match,
+                entry_fee,
+                payout_percentages,
+            } => {
+                let organizer_chain_id = self.runtime.chain_id().to_string();
+                let tournament_id = self.runtime.system_time().micros().to_string();
+                if format == TournamentFormat::Arena && (time_control.is_none() || duration_minutes.is_none()) {
+                    panic!("Arena tournaments need a time control and a duration");
+                }
+                let hub_chain_id = self.runtime.application_parameters().hub_chain_id;
+                if hub_chain_id.is_some_and(|hub| hub != organizer_chain_id) {
+                    panic!("Tournaments are organized on the hub chain");
+                }
+                self.check_game_settings(Variant::Standard, time_control);
+                let mut tournament = Tournament::new(
+                    tournament_id,
+                    name,
+                    format,
+                    organizer_chain_id,
+                    rounds.unwrap_or(0),
+                    time_control,
+                    duration_minutes.unwrap_or(0) as u64 * 60,
+                    mini_match,
+                );
+                if tournament.mini_match.as_ref().is_some_and(|s| s.armageddon_tiebreak) {
+                    self.check_game_settings(Variant::Armageddon, time_control);
+                }
+                tournament.prize_pool = match PrizePool::new(entry_fee, payout_percentages) {
+                    Ok(pool) => pool,
+                    Err(e) => panic!("Invalid prize pool: {}", e),
+                };
+                if tournament.prize_pool.entry_fee.is_some() {
+                    self.wager_token(); // Fails early without a configured token
+                }
+                self.save_tournament(&tournament);
+            }
+
+            Operation::RegisterForTournament {
+                organizer_chain_id,
+                tournament_id,
+                player_name,
+                entry_fee,
+            } => {
+                let target_chain: ChainId = organizer_chain_id.parse().expect("Invalid organizer chain ID");
+                let player_chain_id = self.runtime.chain_id();
+                let owner = self.runtime.authenticated_signer();
+                let player_name = self.display_name(player_name);
+                if let Some(amount) = entry_fee {
+                    self.deposit_to_app(target_chain, amount);
+                }
+                self.runtime.send_message(
+                    target_chain,
+                    CrossChainMessage::TournamentRegistration {
+                        tournament_id,
+                        player_chain_id,
+                        player_name,
+                        owner,
+                        entry_fee,
+                    },
+                );
+            }
+
+            Operation::SponsorTournament {
+                organizer_chain_id,
+                tournament_id,
+                amount,
+            } => {
+                let target_chain: ChainId = organizer_chain_id.parse().expect("Invalid organizer chain ID");
+                let sponsor_chain_id = self.runtime.chain_id();
+                let owner = self.deposit_to_app(target_chain, amount);
+                self.runtime.send_message(
+                    target_chain,
+                    CrossChainMessage::TournamentSponsorship {
+                        tournament_id,
+                        sponsor_chain_id,
+                        owner,
+                        amount,
+                    },
+                );
+            }
+
+            Operation::StartTournament { tournament_id } => {
+                let mut tournament = self
+                    .load_tournament(&tournament_id)
+                    .await
+                    .expect("Tournament not found");
+                if !tournament.rounds.is_empty() {
+                    panic!("Tournament already started");
+                }
+                self.advance_tournament(&mut tournament);
+                self.save_tournament(&tournament);
+            }
+
+            Operation::CloseTournament { tournament_id } => {
+                let now = self.runtime.system_time().micros();
+                let mut tournament = self
+                    .load_tournament(&tournament_id)
+                    .await
+                    .expect("Tournament not found");
+                if let Err(e) = tournament.close(now) {
+                    panic!("Cannot close tournament: {}", e);
+                }
+                self.distribute_prizes(&mut tournament);
+                self.save_tournament(&tournament);
+            }
+
+            Operation::CancelTournament { tournament_id } => {
+                let mut tournament = self
+                    .load_tournament(&tournament_id)
+                    .await
+                    .expect("Tournament not found");
+                if let Err(e) = tournament.cancel() {
+                    panic!("Cannot cancel tournament: {}", e);
+                }
+                self.refund_prize_pool(&mut tournament);
+                self.save_tournament(&tournament);
+            }
+
+            Operation::OfferRematch => {
+                let chain_id_for_message = self.acting_chain_id();
+                let self_chain = chain_id_for_message.to_string();
+
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
+                } else {
+                    panic!("Match not found");
+                };
+                if game.status != MatchStatus::Ended || game.players.len() != 2 {
+                    panic!("Rematch is only possible after a finished game");
+                }
+                if game.tournament.is_some() {
+                    panic!("Tournament games cannot be rematched");
+                }
+                game.rematch_offered_by = Some(self_chain.clone());
+
+                let opponent_chain_id = game
+                    .opponent_of(&self_chain)
+                    .and_then(|p| p.chain_id.parse().ok());
+                if let Some(opponent) = opponent_chain_id {
+                    self.runtime.send_message(
+                        opponent,
+                        CrossChainMessage::RematchOffer {
+                            player_chain_id: chain_id_for_message,
+                        },
+                    );
+                }
+            }
+
+            Operation::AcceptRematch => {
+                let chain_id_for_message = self.acting_chain_id();
+                let self_chain = chain_id_for_message.to_string();
+
+                let game = if let Some(game) = self.state.game.get() {
+                    game
+                } else {
+                    panic!("Match not found");
+                };
+                let offered_by = game.rematch_offered_by.clone().expect("No rematch offered");
+                if offered_by == self_chain {
+                    panic!("Cannot accept your own rematch offer");
+                }
+
+                // The previous guest hosts the rematch so that colours swap
+                if game.host_chain_id != self_chain {
+                    self.start_rematch();
+                } else {
+                    let opponent: ChainId = offered_by.parse().expect("Invalid opponent chain ID");
+                    self.runtime.send_message(
+                        opponent,
+                        CrossChainMessage::RematchAccepted {
+                            player_chain_id: chain_id_for_message,
+                        },
+                    );
+                }
+            }
+        }
+        ChessResponse::Ok
+    }
+
+    async fn execute_message(&mut self, message: Self::Message) {
+        match message {
+            CrossChainMessage::JoinRequest {
+                player_chain_id,
+                player_name,
+                owner,
+                stake,
+            } => {
+                // Turned down requests get their stake back along with the decline
+                let self_chain = self.runtime.chain_id().to_string();
+                let joinable = match self.state.game.get() {
+                    Some(game) => {
+                        game.host_chain_id == self_chain
+                            && game.status == MatchStatus::WaitingForPlayer
+                            && game.players.len() < 2
+                            // Co-owning a game chain needs a signer
+                            && !(game.shared_chain && owner.is_none())
+                            && stake == game.wager
+                            && !(game.wager.is_some() && owner.is_none())
+                    }
+                    None => false,
+                };
+                let already_requested = self
+                    .state
+                    .pending_joins
+                    .get()
+                    .iter()
+                    .any(|p| p.player_chain_id == player_chain_id.to_string());
+                if !joinable || already_requested {
+                    self.decline_join(player_chain_id, owner, stake);
+                    return;
+                }
+
+                // Without auto-accept the host picks among the requests
+                if self.state.settings.get().auto_accept == AutoAcceptPolicy::Nobody {
+                    self.state.pending_joins.get_mut().push(PendingJoin {
+                        player_chain_id: player_chain_id.to_string(),
+                        player_name,
+                        owner,
+                        stake,
+                        requested_at: self.runtime.system_time().micros().to_string(),
+                    });
+                    self.notify(NotificationKind::JoinRequested, Some(player_chain_id.to_string()));
+                    return;
+                }
+                self.accept_join(player_chain_id, player_name, owner, stake);
+            }
+
+            CrossChainMessage::CollectibleTransfer {
+                mut collectible,
+                recipient_owner,
+            } => {
+                // A bounced transfer puts the collectible back as it was
+                if self.runtime.message_is_bouncing() != Some(true) {
+                    collectible.owner = recipient_owner;
+                    let sender = self.runtime.message_origin_chain_id().map(|c| c.to_string());
+                    self.notify_about(
+                        NotificationKind::CollectibleReceived,
+                        Some(collectible.match_id.clone()),
+                        sender,
+                    );
+                }
+                self.state
+                    .collectibles
+                    .insert(&collectible.collectible_id.clone(), collectible)
+                    .expect("Failed to store collectible");
+            }
+
+            CrossChainMessage::JoinDeclined { host_chain_id } => {
+                self.notify(NotificationKind::JoinDeclined, Some(host_chain_id.to_string()));
+            }
+
+            CrossChainMessage::InitialStateSync { game } => {
+                let host_chain_id = game.host_chain_id.clone();
+                let busy = self
+                    .state
+                    .game
+                    .get()
+                    .as_ref()
+                    .map(|g| g.status != MatchStatus::Ended && g.match_id != game.match_id)
+                    .unwrap_or(false);
+                if busy && game.tournament.is_none() {
+                    // Keep our unfinished game, e.g. against a stale rematch or join acceptance,
+                    // and let the sender abort theirs
+                    let origin = match self.runtime.message_origin_chain_id() {
+                        Some(origin) => origin,
+                        None => return,
+                    };
+                    let player_chain_id = self.runtime.chain_id();
+                    self.runtime
+                        .send_message(origin, CrossChainMessage::AbortNotice { player_chain_id });
+                    return;
+                }
+                if let (true, Some(tournament)) = (busy, game.tournament.clone()) {
+                    // Keep our unfinished game and forfeit the tournament game instead
+                    let organizer: ChainId = tournament
+                        .organizer_chain_id
+                        .parse()
+                        .expect("Invalid organizer chain ID");
+                    let player_chain_id = self.runtime.chain_id();
+                    self.notify_about(
+                        NotificationKind::TournamentGameForfeited,
+                        Some(game.match_id.clone()),
+                        Some(host_chain_id),
+                    );
+                    self.runtime.send_message(
+                        organizer,
+                        CrossChainMessage::TournamentGameForfeit { tournament, player_chain_id },
+                    );
+                    return;
+                }
+                self.state.game.set(Some(game.clone()));
+                self.reset_local_state();
+                self.notify(NotificationKind::MatchReady, Some(host_chain_id));
+                self.publish(ChessEvent::GameStarted { game: Box::new(game) });
+            }
+
+            CrossChainMessage::HostGame { game } => {
+                // Only a chain opened for this very game takes it over
+                let this_chain = self.runtime.chain_id().to_string();
+                if !game.is_game_chain(&this_chain) || self.state.game.get().is_some() {
+                    return;
+                }
+                self.state.game.set(Some(game.clone()));
+                self.reset_local_state();
+                self.publish(ChessEvent::GameStarted { game: Box::new(game) });
+            }
+
+            CrossChainMessage::GameChainResult { game } => {
+                let self_chain = self.runtime.chain_id().to_string();
+                // Only the game chain itself may report the result, which drives stats and stake payouts
+                let origin = self.runtime.message_origin_chain_id().map(|c| c.to_string());
+                let expected = match self.state.game.get() {
+                    Some(local) => {
+                        local.match_id == game.match_id
+                            && local.game_chain_id.is_some()
+                            && local.game_chain_id == game.game_chain_id
+                            && origin == local.game_chain_id
+                    }
+                    None => false,
+                };
+                if !expected {
+                    return; // Not the game we are playing, skip
+                }
+                let opponent_chain_id = game.opponent_of(&self_chain).map(|p| p.chain_id.clone());
+                self.state.game.set(Some(game));
+                self.notify(NotificationKind::GameEnded, opponent_chain_id);
+                self.finalize_game();
+            }
+
+            CrossChainMessage::GameChainEvent { event } => {
+                // Only the game chain of the match we are playing relays its events
+                let origin = self.runtime.message_origin_chain_id().map(|c| c.to_string());
+                let game = match self.state.game.get_mut() {
+                    Some(game)
+                        if game.match_id == event.match_id()
+                            && game.game_chain_id.is_some()
+                            && origin == game.game_chain_id =>
+                    {
+                        game
+                    }
+                    _ => return, // Not from our match's game chain, skip
+                };
+                game.apply_event(&event);
+                self.publish(event);
+            }
+
+            CrossChainMessage::GameChainAbandonment { chain_id } => {
+                let origin = self.runtime.message_origin_chain_id().map(|c| c.to_string());
+                let expected = match self.state.game.get() {
+                    Some(local) => {
+                        local.game_chain_id.is_some()
+                            && origin == local.game_chain_id
+                            && local.color_of(&chain_id).is_some()
+                    }
+                    None => false,
+                };
+                if !expected {
+                    return; // Not from our match's game chain, skip
+                }
+                self.record_abandonment(chain_id).await;
+            }
+
+            CrossChainMessage::ResyncRequest {
+                player_chain_id,
+                ply: _,
+            } => {
+                // The host's copy is authoritative: answer with the full game
+                let self_chain = self.runtime.chain_id().to_string();
+                let game = match self.state.game.get() {
+                    Some(game) if game.host_chain_id == self_chain => game.clone(),
+                    _ => return, // Not hosting a game, skip
+                };
+                if game.color_of(&player_chain_id.to_string()).is_none() {
+                    return;
+                }
+                self.runtime
+                    .send_message(player_chain_id, CrossChainMessage::GameSync { game });
+                self.notify(NotificationKind::ResyncRequested, Some(player_chain_id.to_string()));
+            }
+
+            CrossChainMessage::ReadyNotice { player_chain_id, match_id } => {
+                if !self.is_from_opponent(player_chain_id) {
+                    return; // Not sent by our opponent's chain, skip
+                }
+                // A late notice from an earlier match does not start this one
+                let waiting = self
+                    .state
+                    .game
+                    .get()
+                    .as_ref()
+                    .map(|g| g.status == MatchStatus::WaitingForReady && g.match_id == match_id)
+                    .unwrap_or(false);
+                if !waiting {
+                    return;
+                }
+                self.state.opponent_ready.set(true);
+                self.notify(NotificationKind::OpponentReady, Some(player_chain_id.to_string()));
+                self.activate_if_ready();
+            }
+
+            CrossChainMessage::GameSync { game } => {
+                // Only the host of the match we are playing may overwrite our copy of it
+                let origin = self.runtime.message_origin_chain_id().map(|c| c.to_string());
+                let from_host = match self.state.game.get() {
+                    Some(local) => {
+                        local.match_id == game.match_id && origin.as_deref() == Some(local.host_chain_id.as_str())
+                    }
+                    None => false,
+                };
+                if !from_host {
+                    return; // Not from the host of our match, skip
+                }
+                let host_chain_id = game.host_chain_id.clone();
+                for divergence in self.state.divergences.get_mut().iter_mut() {
+                    if divergence.match_id == game.match_id {
+                        divergence.resolved = true;
+                    }
+                }
+                self.state.move_sequencer.set(None);
+                // Same match, so the ready handshake stays where it is
+                let ended = game.status == MatchStatus::Ended;
+                self.state.game.set(Some(game));
+                self.notify(NotificationKind::GameSynced, Some(host_chain_id));
+                if ended {
+                    self.finalize_game();
+                }
+            }
+
+            CrossChainMessage::MoveSync {
+                chess_move,
+                player_chain_id,
+                clock_remaining_micros,
+                ply,
+                position_hash,
+            } => {
+                let (match_id, expected_ply, local_move) = match self.state.game.get() {
+                    Some(game) if game.status == MatchStatus::Active && game.players.len() == 2 => (
+                        game.match_id.clone(),
+                        game.move_history.len() as u32 + 1,
+                        ply.checked_sub(1)
+                            .and_then(|i| game.move_history.get(i as usize))
+                            .map(|m| m.chess_move.to_uci()),
+                    ),
+                    _ => return, // No game to apply the move to, skip
+                };
+                let mut sequencer = self
+                    .state
+                    .move_sequencer
+                    .get()
+                    .clone()
+                    .filter(|s| s.match_id == match_id)
+                    .unwrap_or_else(|| MoveSequencer::new(&match_id));
+                let incoming = SequencedMove {
+                    ply,
+                    chess_move,
+                    player_chain_id: player_chain_id.to_string(),
+                    clock_remaining_micros,
+                    position_hash,
+                };
+                let outcome = sequencer.receive(incoming.clone(), expected_ply);
+                self.state.move_sequencer.set(Some(sequencer));
+
+                match outcome {
+                    SequenceOutcome::Apply => {
+                        self.apply_opponent_move(incoming);
+                        self.apply_buffered_moves();
+                    }
+                    SequenceOutcome::Duplicate => {
+                        // Same ply seen before: harmless if it is the move we have, a divergence otherwise
+                        if local_move.is_some() && local_move != Some(incoming.chess_move.to_uci()) {
+                            self.report_divergence(DivergenceKind::StaleMove, ply, Some(position_hash), None);
+                        }
+                    }
+                    SequenceOutcome::Buffered => {} // Applied once the missing plies arrive
+                    SequenceOutcome::Gap => {
+                        self.state.move_sequencer.set(None);
+                        self.report_divergence(DivergenceKind::PlyGap, ply, Some(position_hash), None);
+                    }
+                }
+            }
+
+            CrossChainMessage::TimeoutNotice { player_chain_id } => {
+                if !self.is_from_opponent(player_chain_id) {
+                    return; // Not sent by our opponent's chain, skip
+                }
+                let self_chain = self.runtime.chain_id().to_string();
+                let now = self.runtime.system_time().micros();
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
+                } else {
+                    return; // Match not found, skip
+                };
+                if game.status != MatchStatus::Active {
+                    return;
+                }
+                // Check the claim against our own clock, which has run since the opponent's last move
+                let flagged = match (game.clock.as_ref(), game.color_of(&self_chain)) {
+                    (Some(clock), Some(my_color)) if game.current_turn == my_color => {
+                        let since = game
+                            .last_move_at
+                            .as_deref()
+                            .and_then(|t| t.parse().ok())
+                            .or(clock.running_since);
+                        since.is_some_and(|since| clock.remaining_since(my_color, since, now) == 0)
+                    }
+                    _ => false,
+                };
+                if !flagged {
+                    return; // We still have time, skip
+                }
+
+                game.status = MatchStatus::Ended;
+                game.termination = Some(Termination::Timeout);
+                game.winner_chain_id = Some(player_chain_id.to_string());
+                self.notify(NotificationKind::LostOnTime, Some(player_chain_id.to_string()));
+                self.finalize_game();
+            }
+
+            CrossChainMessage::BerserkNotice { player_chain_id } => {
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
+                } else {
+                    return; // Match not found, skip
+                };
+                let color = if let Some(color) = game.color_of(&player_chain_id.to_string()) {
+                    color
+                } else {
+                    return;
+                };
+                if let Some(clock) = game.clock.as_mut() {
+                    if !clock.is_berserk(color) {
+                        clock.berserk(color);
+                    }
+                }
+                self.notify(NotificationKind::OpponentBerserk, Some(player_chain_id.to_string()));
+            }
+
+            CrossChainMessage::ResignNotice { player_chain_id } => {
+                if !self.is_from_opponent(player_chain_id) {
+                    return; // Not sent by our opponent's chain, skip
+                }
+                // Use get_mut() to modify game state through View system
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
+                } else {
+                    return; // Match not found, skip
+                };
+                if game.status != MatchStatus::Active && game.status != MatchStatus::WaitingForReady {
+                    return; // Already over, skip
+                }
+
+                game.status = MatchStatus::Ended;
+                game.termination = Some(Termination::Resignation);
+                // Winner is the one who didn't resign
+                let self_chain = self.runtime.chain_id().to_string();
+                game.winner_chain_id = Some(self_chain);
+                self.notify(NotificationKind::OpponentResigned, Some(player_chain_id.to_string()));
+                self.finalize_game();
+            }
+
+            CrossChainMessage::GameEndNotice {
+                player_chain_id,
+                status,
+            } => {
+                if !self.is_from_opponent(player_chain_id) {
+                    return; // Not sent by our opponent's chain, skip
+                }
+                // Use get_mut() to modify game state through View system
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
+                } else {
+                    return; // Match not found, skip
+                };
+                if game.status != MatchStatus::Active
+                    || game.wager.is_some()
+                    || game.rated
+                    || game.tournament.is_some()
+                    || game.series.is_some()
+                {
+                    return; // Only casual games in play can be ended this way, skip
+                }
+
+                game.status = status;
+                self.notify(NotificationKind::GameEnded, Some(player_chain_id.to_string()));
+                self.finalize_game();
+            }
+
+            CrossChainMessage::AbortNotice { player_chain_id } => {
+                if !self.is_from_opponent(player_chain_id) {
+                    return; // Not sent by our opponent's chain, skip
+                }
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
+                } else {
+                    return; // Match not found, skip
+                };
+                if game.status == MatchStatus::Ended {
+                    return;
+                }
+
+                game.status = MatchStatus::Ended;
+                game.termination = Some(Termination::Aborted);
+                game.winner_chain_id = None;
+                self.notify(NotificationKind::OpponentAborted, Some(player_chain_id.to_string()));
+                self.finalize_game();
+            }
+
+            CrossChainMessage::TakebackRequested {
+                player_chain_id,
+                plies,
+                at_ply,
+            } => {
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
+                } else {
+                    return; // Match not found, skip
+                };
+                if game.status != MatchStatus::Active || game.rated || game.tournament.is_some() {
+                    return;
+                }
+                let request = TakebackRequest {
+                    requested_by: player_chain_id.to_string(),
+                    plies,
+                    at_ply,
+                };
+                game.takeback_request = Some(request.clone());
+                let event = ChessEvent::TakebackRequested {
+                    match_id: game.match_id.clone(),
+                    request,
+                };
+                self.notify(NotificationKind::TakebackRequested, Some(player_chain_id.to_string()));
+                self.publish(event);
+            }
+
+            CrossChainMessage::TakebackAccepted {
+                player_chain_id,
+                plies,
+                at_ply,
+            } => {
+                let now = self.runtime.system_time().micros();
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
+                } else {
+                    return; // Match not found, skip
+                };
+                if game.status != MatchStatus::Active {
+                    return;
+                }
+                if Self::apply_takeback(game, plies, at_ply, now).is_err() {
+                    return;
+                }
+                let event = ChessEvent::TakebackApplied {
+                    match_id: game.match_id.clone(),
+                    moves_left: game.move_history.len() as u32,
+                };
+                self.state.move_sequencer.set(None);
+                self.notify(NotificationKind::TakebackAccepted, Some(player_chain_id.to_string()));
+                self.publish(event);
+            }
+
+            CrossChainMessage::AbandonmentClaimed {
+                player_chain_id,
+                aborted,
+            } => {
+                if !self.is_from_opponent(player_chain_id) {
+                    return; // Not sent by our opponent's chain, skip
+                }
+                let self_chain = self.runtime.chain_id().to_string();
+
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
+                } else {
+                    return; // Match not found, skip
+                };
+                if game.status != MatchStatus::Active && game.status != MatchStatus::WaitingForReady {
+                    return;
+                }
+
+                game.status = MatchStatus::Ended;
+                if aborted {
+                    game.termination = Some(Termination::Aborted);
+                    game.winner_chain_id = None;
+                } else {
+                    game.termination = Some(Termination::Abandonment);
+                    game.winner_chain_id = Some(player_chain_id.to_string());
+                }
+                self.notify(NotificationKind::GameForfeited, Some(player_chain_id.to_string()));
+                self.record_abandonment(self_chain).await;
+                self.finalize_game();
+            }
+
+            CrossChainMessage::DrawOffer { player_chain_id } => {
+                if !self.is_from_opponent(player_chain_id) {
+                    return; // Not sent by our opponent's chain, skip
+                }
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
+                } else {
+                    return; // Match not found, skip
+                };
+                if game.status != MatchStatus::Active {
+                    return;
+                }
+                game.draw_offered_by = Some(player_chain_id.to_string());
+                let event = ChessEvent::DrawOffered {
+                    match_id: game.match_id.clone(),
+                    offered_by: player_chain_id.to_string(),
+                };
+                self.notify(NotificationKind::DrawOffered, Some(player_chain_id.to_string()));
+                self.publish(event);
+            }
+
+            CrossChainMessage::DrawAccepted { player_chain_id } => {
+                if !self.is_from_opponent(player_chain_id) {
+                    return; // Not sent by our opponent's chain, skip
+                }
+                let self_chain = self.runtime.chain_id().to_string();
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
+                } else {
+                    return; // Match not found, skip
+                };
+                if game.status != MatchStatus::Active
+                    || game.draw_offered_by.as_deref() != Some(self_chain.as_str())
+                {
+                    return;
+                }
+
+                game.status = MatchStatus::Ended;
+                game.termination = Some(Termination::Agreement);
+                game.winner_chain_id = None;
+                game.draw_offered_by = None;
+                self.notify(NotificationKind::DrawAgreed, Some(player_chain_id.to_string()));
+                self.finalize_game();
+            }
+
+            CrossChainMessage::TournamentRegistration {
+                tournament_id,
+                player_chain_id,
+                player_name,
+                owner,
+                entry_fee,
+            } => {
+                let registered = match self.load_tournament(&tournament_id).await {
+                    None => Err("Tournament not found".to_string()),
+                    Some(tournament) if entry_fee != tournament.prize_pool.entry_fee => {
+                        Err("Entry fee does not match".to_string())
+                    }
+                    Some(mut tournament) => tournament
+                        .register(player_chain_id.to_string(), player_name, owner)
+                        .map(|()| tournament),
+                };
+                let mut tournament = match (registered, entry_fee, owner) {
+                    (Ok(tournament), _, _) => tournament,
+                    // A paid registration that cannot be accepted gets its fee back
+                    (Err(_), Some(amount), Some(owner)) => {
+                        let account = Account { chain_id: player_chain_id, owner };
+                        self.pay_from_app(amount, account);
+                        return;
+                    }
+                    (Err(e), _, _) => panic!("Registration failed: {}", e),
+                };
+                if let (Some(amount), Some(owner)) = (entry_fee, owner) {
+                    tournament.prize_pool.add_deposit(PoolDeposit {
+                        kind: DepositKind::EntryFee,
+                        chain_id: player_chain_id.to_string(),
+                        owner,
+                        amount,
+                        timestamp: self.runtime.system_time().micros().to_string(),
+                    });
+                }
+                self.notify_about(
+                    NotificationKind::TournamentRegistration,
+                    None,
+                    Some(player_chain_id.to_string()),
+                );
+                // Late arena entrants are paired right away
+                if tournament.arena.is_some() && tournament.status == TournamentStatus::InProgress {
+                    self.advance_tournament(&mut tournament);
+                }
+                self.save_tournament(&tournament);
+            }
+
+            CrossChainMessage::TournamentSponsorship {
+                tournament_id,
+                sponsor_chain_id,
+                owner,
+                amount,
+            } => {
+                let mut tournament = match self.load_tournament(&tournament_id).await {
+                    Some(tournament) if !tournament.prize_pool.distributed => tournament,
+                    _ => {
+                        // Unknown or already paid out: refund the sponsor
+                        let account = Account { chain_id: sponsor_chain_id, owner };
+                        self.pay_from_app(amount, account);
+                        return;
+                    }
+                };
+                tournament.prize_pool.add_deposit(PoolDeposit {
+                    kind: DepositKind::Sponsorship,
+                    chain_id: sponsor_chain_id.to_string(),
+                    owner,
+                    amount,
+                    timestamp: self.runtime.system_time().micros().to_string(),
+                });
+                self.notify_about(
+                    NotificationKind::TournamentSponsored,
+                    None,
+                    Some(sponsor_chain_id.to_string()),
+                );
+                self.save_tournament(&tournament);
+            }
+
+            CrossChainMessage::TournamentGameStart {
+                tournament,
+                white,
+                black,
+                time_control,
+                armageddon,
+            } => {
+                let now = self.runtime.system_time().micros();
+                let timestamp = now.to_string();
+                let busy = self
+                    .state
+                    .game
+                    .get()
+                    .as_ref()
+                    .map(|g| g.status != MatchStatus::Ended)
+                    .unwrap_or(false);
+                if busy {
+                    // Forfeit the game instead of dropping the message, so the round goes on
+                    let organizer: ChainId = tournament
+                        .organizer_chain_id
+                        .parse()
+                        .expect("Invalid organizer chain ID");
+                    let player_chain_id = self.runtime.chain_id();
+                    self.notify_about(
+                        NotificationKind::TournamentGameForfeited,
+                        None,
+                        Some(black.chain_id.clone()),
+                    );
+                    self.runtime.send_message(
+                        organizer,
+                        CrossChainMessage::TournamentGameForfeit { tournament, player_chain_id },
+                    );
+                    return;
+                }
+
+                // White hosts the tournament game, exactly like a joined CreateMatch
+                let black_chain_id = black.chain_id.clone();
+                let black_chain: ChainId = black.chain_id.parse().expect("Invalid player chain ID");
+                let game = Game {
+                    match_id: timestamp.clone(),
+                    host_chain_id: white.chain_id.clone(),
+                    status: MatchStatus::Active,
+                    players: vec![white, black],
+                    current_turn: Color::White,
+                    board: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
+                    move_history: Vec::new(),
+                    created_at: timestamp.clone(),
+                    started_at: Some(timestamp),
+                    ready_deadline_micros: None,
+                    last_move_at: None,
+                    winner_chain_id: None,
+                    termination: None,
+                    rated: true,
+                    inactivity_limit_secs: DEFAULT_INACTIVITY_LIMIT_SECS,
+                    takeback_request: None,
+                    clock: time_control.map(|time_control| {
+                        let mut clock = if armageddon {
+                            Clock::armageddon(time_control)
+                        } else {
+                            Clock::new(time_control)
+                        };
+                        clock.start(now);
+                        clock
+                    }),
+                    series: None,
+                    tournament: Some(tournament),
+                    armageddon,
+                    rematch_offered_by: None,
+                    draw_offered_by: None,
+                    shared_chain: false,
+                    game_chain_id: None,
+                    wager: None,
+                    spectators_allowed: true,
+                    engine_level: None,
+                };
+                self.state.game.set(Some(game.clone()));
+                self.reset_local_state();
+                self.notify(NotificationKind::TournamentGameStarted, Some(black_chain_id));
+                self.publish(ChessEvent::GameStarted { game: Box::new(game.clone()) });
+                self.runtime
+                    .send_message(black_chain, CrossChainMessage::InitialStateSync { game });
+            }
+
+            CrossChainMessage::TournamentGameResult {
+                tournament_id,
+                round,
+                board,
+                game_number,
+                match_id,
+                winner_chain_id,
+                white_berserk,
+                black_berserk,
+            } => {
+                let mut tournament = if let Some(tournament) = self.load_tournament(&tournament_id).await {
+                    tournament
+                } else {
+                    return; // Unknown tournament, skip
+                };
+                // Only the two players of the board may report its games
+                let origin = self.runtime.message_origin_chain_id().map(|c| c.to_string());
+                let from_player = match (tournament.pairing(round, board), origin) {
+                    (Some(pairing), Some(origin)) => pairing.opponent_of(&origin).is_some(),
+                    _ => false,
+                };
+                if !from_player {
+                    return; // Not sent by a player of this board, skip
+                }
+                let record = tournament.record_game(
+                    round,
+                    board,
+                    game_number,
+                    match_id.clone(),
+                    winner_chain_id,
+                    white_berserk,
+                    black_berserk,
+                );
+                if record == GameRecord::Ignored {
+                    return; // Duplicate or stale report
+                }
+                self.notify_about(NotificationKind::TournamentGameReported, Some(match_id), None);
+                self.continue_tournament(&mut tournament, round, board, record);
+            }
+
+            CrossChainMessage::TournamentGameForfeit {
+                tournament: game_ref,
+                player_chain_id,
+            } => {
+                let origin = self.runtime.message_origin_chain_id().map(|c| c.to_string());
+                let self_chain = self.runtime.chain_id().to_string();
+                let forfeiter = player_chain_id.to_string();
+
+                // On the organizer's chain, from the busy player: the game is scored for the opponent
+                if origin.as_deref() == Some(forfeiter.as_str()) {
+                    if let Some(mut tournament) = self.load_tournament(&game_ref.tournament_id).await {
+                        let scheduled = match tournament.next_game(game_ref.round, game_ref.board) {
+                            Some(scheduled) if scheduled.game_number == game_ref.game_number => scheduled,
+                            _ => return, // Already scored, skip
+                        };
+                        let winner_chain_id = if scheduled.white_chain_id == forfeiter {
+                            scheduled.black_chain_id.clone()
+                        } else if scheduled.black_chain_id == forfeiter {
+                            scheduled.white_chain_id.clone()
+                        } else {
+                            return; // Not a player of this game, skip
+                        };
+                        let match_id = format!(
+                            "{}-{}-{}-{}-forfeit",
+                            game_ref.tournament_id, game_ref.round, game_ref.board, game_ref.game_number
+                        );
+                        let record = tournament.record_game(
+                            game_ref.round,
+                            game_ref.board,
+                            game_ref.game_number,
+                            match_id.clone(),
+                            Some(winner_chain_id.clone()),
+                            false,
+                            false,
+                        );
+                        if record == GameRecord::Ignored {
+                            return;
+                        }
+                        // A busy arena player sits out the rest of the arena instead of forfeiting game after game
+                        if let Some(arena) = tournament.arena.as_mut() {
+                            arena.waiting.retain(|chain_id| *chain_id != forfeiter);
+                        }
+                        self.notify_about(
+                            NotificationKind::TournamentGameForfeited,
+                            Some(match_id),
+                            Some(forfeiter.clone()),
+                        );
+                        // White has already started the game Black cannot join: tell them to end it
+                        if scheduled.black_chain_id == forfeiter {
+                            let host: ChainId = winner_chain_id.parse().expect("Invalid player chain ID");
+                            self.runtime.send_message(
+                                host,
+                                CrossChainMessage::TournamentGameForfeit { tournament: game_ref.clone(), player_chain_id },
+                            );
+                        }
+                        self.continue_tournament(&mut tournament, game_ref.round, game_ref.board, record);
+                        return;
+                    }
+                }
+
+                // On the host's chain, passed on by the organizer: end the game Black never joined
+                if origin.as_deref() != Some(game_ref.organizer_chain_id.as_str()) {
+                    return;
+                }
+                let hosted = self
+                    .state
+                    .game
+                    .get()
+                    .as_ref()
+                    .map(|g| {
+                        g.host_chain_id == self_chain
+                            && g.status != MatchStatus::Ended
+                            && g.opponent_of(&self_chain).map(|p| p.chain_id.as_str()) == Some(forfeiter.as_str())
+                            && g.tournament.as_ref().is_some_and(|t| {
+                                t.tournament_id == game_ref.tournament_id
+                                    && t.round == game_ref.round
+                                    && t.board == game_ref.board
+                                    && t.game_number == game_ref.game_number
+                            })
+                    })
+                    .unwrap_or(false);
+                if !hosted {
+                    return;
+                }
+                if let Some(game) = self.state.game.get_mut() {
+                    game.status = MatchStatus::Ended;
+                    game.winner_chain_id = Some(self_chain);
+                    game.termination = Some(Termination::Abandonment);
+                }
+                self.notify(NotificationKind::TournamentGameForfeited, Some(forfeiter));
+                // The organizer has already scored it, so the report is dropped as a duplicate
+                self.finalize_game();
+            }
+
+            CrossChainMessage::RematchOffer { player_chain_id } => {
+                let game = if let Some(game) = self.state.game.get_mut() {
+                    game
+                } else {
+                    return; // Match not found, skip
+                };
+                if game.status != MatchStatus::Ended {
+                    return;
+                }
+                game.rematch_offered_by = Some(player_chain_id.to_string());
+                self.notify(NotificationKind::RematchOffered, Some(player_chain_id.to_string()));
+            }
+
+            CrossChainMessage::RematchAccepted { player_chain_id: _ } => {
+                let self_chain = self.runtime.chain_id().to_string();
+                let offered_by_me = self
+                    .state
+                    .game
+                    .get()
+                    .as_ref()
+                    .map(|g| g.rematch_offered_by.as_deref() == Some(self_chain.as_str()))
+                    .unwrap_or(false);
+                if !offered_by_me {
+                    return;
+                }
+                self.start_rematch();
+            }
+        }
+    }
+
+    async fn process_streams(
+        &mut self,
+        streams: Vec<linera_sdk::linera_base_types::StreamUpdate>,
+    ) {
+        // Spectated games: replay every new event from the host's stream
+        for update in streams {
+            for index in update.new_indices() {
+                let event = self
+                    .runtime
+                    .read_event(update.chain_id, update.stream_id.stream_name.clone(), index);
+                self.apply_spectator_event(event).await;
+            }
+        }
     }
 
     async fn store(mut self) {
@@ -0,0 +1,429 @@
+// Built-in opponent and analysis: a deterministic alpha-beta search on shakmaty.
+// The same position and budget always give the same move, so every validator agrees on
+// the engine's replies.
+
+use async_graphql::{Enum, SimpleObject};
+use serde::{Deserialize, Serialize};
+use shakmaty::fen::Fen;
+use shakmaty::san::SanPlus;
+use shakmaty::uci::UciMove;
+use shakmaty::{CastlingMode, Chess, Color as ShakColor, Move, Position, Role};
+
+use crate::{ChessMove, PieceType, Square};
+
+// Player ID of the engine in single-player games; never a valid chain ID, so no message
+// is ever sent to it
+pub const ENGINE_PLAYER_ID: &str = "engine";
+
+// Bounds of a service analysis, well below the fuel a query may use
+pub const MAX_ANALYSIS_DEPTH: u32 = 5;
+pub const ANALYSIS_NODE_LIMIT: u64 = 300_000;
+
+const MATE_SCORE: i32 = 100_000;
+// Scores beyond this are mates, counted in plies from the root
+const MATE_THRESHOLD: i32 = MATE_SCORE - 1_000;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Enum)]
+pub enum EngineLevel {
+    Beginner,
+    Casual,
+    Intermediate,
+    Advanced,
+}
+
+impl EngineLevel {
+    // Deepest full-width iteration
+    pub fn depth(&self) -> u32 {
+        match self {
+            EngineLevel::Beginner => 1,
+            EngineLevel::Casual => 2,
+            EngineLevel::Intermediate => 3,
+            EngineLevel::Advanced => 4,
+        }
+    }
+
+    // Nodes searched at most per move, quiescence included
+    pub fn node_limit(&self) -> u64 {
+        match self {
+            EngineLevel::Beginner => 2_000,
+            EngineLevel::Casual => 10_000,
+            EngineLevel::Intermediate => 50_000,
+            EngineLevel::Advanced => 200_000,
+        }
+    }
+}
+
+// Evaluation from the side to move's point of view
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Score {
+    Centipawns(i32),
+    Mate(i32), // Moves until mate; negative when the side to move gets mated
+}
+
+impl Score {
+    fn from_internal(score: i32) -> Score {
+        if score > MATE_THRESHOLD {
+            Score::Mate((MATE_SCORE - score + 1) / 2)
+        } else if score < -MATE_THRESHOLD {
+            Score::Mate(-(MATE_SCORE + score + 1) / 2)
+        } else {
+            Score::Centipawns(score)
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    pub best_move: Option<Move>, // None if the side to move has no legal move
+    pub score: Score,
+    pub pv: Vec<Move>, // Principal variation, starting with `best_move`
+    pub depth: u32, // Deepest iteration that completed
+    pub nodes: u64,
+}
+
+// Evaluation of a position, from White's point of view
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct Analysis {
+    pub fen: String,
+    pub centipawns: Option<i32>, // None when a mate was found
+    pub mate_in: Option<i32>, // Moves until mate; negative when Black mates
+    pub best_move: Option<String>, // UCI
+    pub principal_variation: Vec<String>, // SAN, starting with the best move
+    pub depth: u32,
+    pub nodes: u64,
+}
+
+// Bounded search from a FEN for analysis
+pub fn analyse(fen: &str, depth: u32, node_limit: u64) -> Result<Analysis, String> {
+    let setup: Fen = fen.parse().map_err(|e| format!("Invalid FEN: {}", e))?;
+    let position: Chess = setup
+        .into_position(CastlingMode::Standard)
+        .map_err(|e| format!("Invalid position: {}", e))?;
+    let result = search(&position, depth.clamp(1, MAX_ANALYSIS_DEPTH), node_limit.min(ANALYSIS_NODE_LIMIT));
+
+    let sign = match position.turn() {
+        ShakColor::White => 1,
+        ShakColor::Black => -1,
+    };
+    let (centipawns, mate_in) = match result.score {
+        Score::Centipawns(score) => (Some(sign * score), None),
+        Score::Mate(moves) => (None, Some(sign * moves)),
+    };
+    let mut principal_variation = Vec::with_capacity(result.pv.len());
+    let mut line = position.clone();
+    for m in &result.pv {
+        principal_variation.push(SanPlus::from_move_and_play_unchecked(&mut line, *m).to_string());
+    }
+    Ok(Analysis {
+        fen: fen.to_string(),
+        centipawns,
+        mate_in,
+        best_move: result
+            .best_move
+            .map(|m| UciMove::from_move(m, CastlingMode::Standard).to_string()),
+        principal_variation,
+        depth: result.depth,
+        nodes: result.nodes,
+    })
+}
+
+struct Search {
+    nodes: u64,
+    node_limit: u64,
+    aborted: bool,
+}
+
+// Iterative deepening up to `max_depth`; an iteration cut short by the node limit is
+// discarded, so the result only depends on the position and the budget
+pub fn search(position: &Chess, max_depth: u32, node_limit: u64) -> SearchResult {
+    let mut search = Search {
+        nodes: 0,
+        node_limit,
+        aborted: false,
+    };
+    let mut result = SearchResult {
+        best_move: None,
+        score: Score::Centipawns(evaluate(position)),
+        pv: Vec::new(),
+        depth: 0,
+        nodes: 0,
+    };
+    let root_moves = position.legal_moves();
+    if root_moves.is_empty() {
+        result.score = Score::from_internal(terminal_score(position, 0));
+        return result;
+    }
+    // Always have a move to play, even if not a single iteration completes
+    result.best_move = order_moves(position, None).first().copied();
+    result.pv = result.best_move.into_iter().collect();
+
+    for depth in 1..=max_depth.max(1) {
+        let mut pv = Vec::new();
+        let score = search.negamax(position, depth, 0, -MATE_SCORE, MATE_SCORE, result.pv.first().copied(), &mut pv);
+        if search.aborted {
+            break;
+        }
+        result.best_move = pv.first().copied();
+        result.score = Score::from_internal(score);
+        result.pv = pv;
+        result.depth = depth;
+        if score.abs() > MATE_THRESHOLD {
+            break; // A forced mate will not change with more depth
+        }
+    }
+    result.nodes = search.nodes;
+    result
+}
+
+impl Search {
+    #[allow(clippy::too_many_arguments)]
+    fn negamax(
+        &mut self,
+        position: &Chess,
+        depth: u32,
+        ply: i32,
+        mut alpha: i32,
+        beta: i32,
+        hint: Option<Move>,
+        pv: &mut Vec<Move>,
+    ) -> i32 {
+        pv.clear();
+        if self.visit() {
+            return 0;
+        }
+        let moves = order_moves(position, hint);
+        if moves.is_empty() {
+            return terminal_score(position, ply);
+        }
+        if position.is_insufficient_material() || position.halfmoves() >= 100 {
+            return 0;
+        }
+        if depth == 0 {
+            return self.quiescence(position, alpha, beta);
+        }
+
+        let mut child_pv = Vec::new();
+        for m in moves {
+            let mut child = position.clone();
+            child.play_unchecked(m);
+            let score = -self.negamax(&child, depth - 1, ply + 1, -beta, -alpha, None, &mut child_pv);
+            if self.aborted {
+                return 0;
+            }
+            if score > alpha {
+                alpha = score;
+                pv.clear();
+                pv.push(m);
+                pv.extend_from_slice(&child_pv);
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+        alpha
+    }
+
+    // Captures and promotions only, until the position is quiet
+    fn quiescence(&mut self, position: &Chess, mut alpha: i32, beta: i32) -> i32 {
+        let stand_pat = evaluate(position);
+        if stand_pat >= beta {
+            return stand_pat;
+        }
+        alpha = alpha.max(stand_pat);
+        for m in order_moves(position, None) {
+            if !m.is_capture() && !m.is_promotion() {
+                continue;
+            }
+            if self.visit() {
+                return 0;
+            }
+            let mut child = position.clone();
+            child.play_unchecked(m);
+            let score = -self.quiescence(&child, -beta, -alpha);
+            if self.aborted {
+                return 0;
+            }
+            if score >= beta {
+                return score;
+            }
+            alpha = alpha.max(score);
+        }
+        alpha
+    }
+
+    // Count a node; true once the budget is spent
+    fn visit(&mut self) -> bool {
+        self.nodes += 1;
+        if self.nodes > self.node_limit {
+            self.aborted = true;
+        }
+        self.aborted
+    }
+}
+
+// Mated positions score worse the sooner the mate; anything else without moves is stalemate
+fn terminal_score(position: &Chess, ply: i32) -> i32 {
+    if position.is_check() {
+        -MATE_SCORE + ply
+    } else {
+        0
+    }
+}
+
+// Legal moves, the hinted move first, then captures by victim and attacker, then promotions.
+// The sort is stable, so equal moves keep shakmaty's generation order.
+fn order_moves(position: &Chess, hint: Option<Move>) -> Vec<Move> {
+    let mut moves: Vec<Move> = position.legal_moves().into_iter().collect();
+    moves.sort_by_key(|m| {
+        if Some(*m) == hint {
+            return i32::MIN;
+        }
+        let capture = m.capture().map(|victim| 10 * piece_value(victim) - piece_value(m.role()));
+        let promotion = m.promotion().map(piece_value).unwrap_or(0);
+        -(capture.unwrap_or(0) + promotion)
+    });
+    moves
+}
+
+fn piece_value(role: Role) -> i32 {
+    match role {
+        Role::Pawn => 100,
+        Role::Knight => 320,
+        Role::Bishop => 330,
+        Role::Rook => 500,
+        Role::Queen => 900,
+        Role::King => 0,
+    }
+}
+
+// Material plus small positional terms, in centipawns for the side to move
+pub fn evaluate(position: &Chess) -> i32 {
+    let board = position.board();
+    let queens_on_board = board.by_role(Role::Queen).any();
+    let mut white_score = 0;
+    for (square, piece) in board.iter() {
+        let file = square.file() as i32;
+        let relative_rank = match piece.color {
+            ShakColor::White => square.rank() as i32,
+            ShakColor::Black => 7 - square.rank() as i32,
+        };
+        // 0 on the rim up to 12 in the four central squares
+        let centrality = 14 - (2 * file - 7).abs() - (2 * square.rank() as i32 - 7).abs();
+        let positional = match piece.role {
+            Role::Pawn => relative_rank * 6 + if file == 3 || file == 4 { 8 } else { 0 },
+            Role::Knight => centrality * 3,
+            Role::Bishop => centrality * 2,
+            Role::Rook => if relative_rank == 6 { 20 } else { 0 },
+            Role::Queen => centrality,
+            // The king hides while queens are on, then walks to the centre
+            Role::King if queens_on_board => -centrality * 2,
+            Role::King => centrality * 2,
+        };
+        let value = piece_value(piece.role) + positional;
+        white_score += match piece.color {
+            ShakColor::White => value,
+            ShakColor::Black => -value,
+        };
+    }
+    match position.turn() {
+        ShakColor::White => white_score,
+        ShakColor::Black => -white_score,
+    }
+}
+
+fn piece_type_of(role: Role) -> PieceType {
+    match role {
+        Role::Pawn => PieceType::Pawn,
+        Role::Knight => PieceType::Knight,
+        Role::Bishop => PieceType::Bishop,
+        Role::Rook => PieceType::Rook,
+        Role::Queen => PieceType::Queen,
+        Role::King => PieceType::King,
+    }
+}
+
+// Our move format for a shakmaty move; castling is given as the king's two-square step
+pub fn to_chess_move(m: Move) -> ChessMove {
+    match UciMove::from_move(m, CastlingMode::Standard) {
+        UciMove::Normal { from, to, promotion } => ChessMove {
+            from: Square::new(from.file() as u8, from.rank() as u8),
+            to: Square::new(to.file() as u8, to.rank() as u8),
+            promotion: promotion.map(piece_type_of),
+        },
+        _ => unreachable!("Standard chess only has normal moves"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(fen: &str) -> Chess {
+        let setup: Fen = fen.parse().unwrap();
+        setup.into_position(CastlingMode::Standard).unwrap()
+    }
+
+    fn uci(m: Move) -> String {
+        UciMove::from_move(m, CastlingMode::Standard).to_string()
+    }
+
+    #[test]
+    fn finds_mate_in_one() {
+        let result = search(&position("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1"), 3, 100_000);
+        assert_eq!(result.best_move.map(uci).as_deref(), Some("a1a8"));
+        assert_eq!(result.score, Score::Mate(1));
+        // A forced mate ends the deepening early
+        assert_eq!(result.depth, 1);
+    }
+
+    #[test]
+    fn analysis_reports_mates_from_whites_point_of_view() {
+        let analysis = analyse("r5k1/8/8/8/8/8/5PPP/6K1 b - - 0 1", 3, 100_000).unwrap();
+        assert_eq!(analysis.best_move.as_deref(), Some("a8a1"));
+        assert_eq!(analysis.mate_in, Some(-1));
+        assert_eq!(analysis.centipawns, None);
+        assert_eq!(analysis.principal_variation, vec!["Ra1#"]);
+    }
+
+    #[test]
+    fn positions_without_moves_have_no_best_move() {
+        // Stalemate
+        let result = search(&position("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"), 3, 100_000);
+        assert!(result.best_move.is_none());
+        assert_eq!(result.score, Score::Centipawns(0));
+        // Checkmate
+        let result = search(&position("R5k1/5ppp/8/8/8/8/5PPP/6K1 b - - 0 1"), 3, 100_000);
+        assert!(result.best_move.is_none());
+        assert_eq!(result.score, Score::Mate(0));
+    }
+
+    #[test]
+    fn node_limit_bounds_the_search_deterministically() {
+        let start = Chess::default();
+        let result = search(&start, 4, 500);
+        assert!(result.nodes <= 501);
+        assert!(result.depth < 4);
+        assert!(result.best_move.is_some());
+
+        let again = search(&start, 4, 500);
+        assert_eq!(again.best_move, result.best_move);
+        assert_eq!(again.pv, result.pv);
+        assert_eq!(again.nodes, result.nodes);
+    }
+
+    #[test]
+    fn castling_converts_to_the_kings_two_square_step() {
+        let position = position("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+        let mut castles: Vec<ChessMove> = position
+            .legal_moves()
+            .into_iter()
+            .filter(|m| m.is_castle())
+            .map(to_chess_move)
+            .collect();
+        castles.sort_by_key(|m| m.to.file);
+        assert_eq!(castles.len(), 2);
+        assert_eq!((castles[0].from.file, castles[0].to.file, castles[0].to.rank), (4, 2, 0));
+        assert_eq!((castles[1].from.file, castles[1].to.file, castles[1].to.rank), (4, 6, 0));
+    }
+}
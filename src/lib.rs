@@ -1,16 +1,32 @@
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
 use serde::{Deserialize, Serialize};
-use linera_sdk::linera_base_types::ChainId;
+use linera_sdk::linera_base_types::{AccountOwner, Amount, ApplicationId, ChainId, StreamName};
 use linera_sdk::abi::{ContractAbi as LineraContractAbi, ServiceAbi as LineraServiceAbi};
 use async_graphql::{Enum, InputObject, SimpleObject, Request, Response};
 
+pub mod collectible;
+pub mod engine;
+pub mod profile;
+pub mod render;
+pub mod tournament;
+
+pub use collectible::Collectible;
+pub use engine::{EngineLevel, ENGINE_PLAYER_ID};
+pub use profile::{GameOutcome, PlayerProfile, PlayerStats, ResultCounts, TimeCategoryStats, VariantStats};
+
+pub use tournament::{
+    Arena, ArenaScore, Bracket, BracketMatch, BracketRound, BracketSide, DepositKind, GameRecord,
+    Pairing, PairingResult, PoolDeposit, PrizePayout, PrizePool, ScheduledGame, Standing, Tournament, TournamentFormat, TournamentPlayer,
+    TournamentRound, TournamentStatus,
+};
+
 // ABI
 pub struct ChessAbi;
 
 impl LineraContractAbi for ChessAbi {
     type Operation = Operation;
-    type Response = ();
+    type Response = ChessResponse;
 }
 
 impl LineraServiceAbi for ChessAbi {
@@ -18,11 +34,115 @@ impl LineraServiceAbi for ChessAbi {
     type QueryResponse = Response;
 }
 
+// Answer to an operation; only the result queries made by other applications carry data
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub enum ChessResponse {
+    #[default]
+    Ok,
+    Result(Option<GameResult>), // None if the match is unknown on this chain
+    Watching, // The result will be published on this chain's `results_stream_name()` stream
+}
+
+// Deployment-wide settings, fixed when the application is created
+#[derive(Debug, Clone, Default, Deserialize, Serialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+#[serde(default)]
+pub struct ChessParameters {
+    // Fungible token for wagers and tournament prize pools; both are disabled without one
+    pub wager_token: Option<ApplicationId>,
+    pub fee_recipient: Option<FeeRecipient>,
+    // Taken from the winnings of staked games and from tournament entry fees
+    pub fee_bps: u32,
+    pub allowed_variants: Vec<Variant>,          // Empty allows every variant
+    pub allowed_time_controls: Vec<TimeControl>, // Empty allows any time control, or none
+    // Chain where public tournaments are organized; any chain may organize them if unset
+    pub hub_chain_id: Option<String>,
+}
+
+// Highest platform fee a deployment may charge: 10%
+pub const MAX_FEE_BPS: u32 = 1_000;
+
+#[derive(Debug, Clone, Deserialize, Serialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct FeeRecipient {
+    pub chain_id: String,
+    pub owner: AccountOwner,
+}
+
+impl ChessParameters {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.fee_bps > MAX_FEE_BPS {
+            return Err(format!("Fee may be at most {} basis points", MAX_FEE_BPS));
+        }
+        if self.fee_bps > 0 && self.fee_recipient.is_none() {
+            return Err("A fee needs a fee recipient".to_string());
+        }
+        Ok(())
+    }
+
+    // Platform fee on `amount`, rounded down
+    pub fn fee_on(&self, amount: Amount) -> Amount {
+        if self.fee_recipient.is_none() {
+            return Amount::ZERO;
+        }
+        Amount::from_attos(u128::from(amount) * self.fee_bps as u128 / 10_000)
+    }
+
+    pub fn allows_variant(&self, variant: Variant) -> bool {
+        self.allowed_variants.is_empty() || self.allowed_variants.contains(&variant)
+    }
+
+    pub fn allows_time_control(&self, time_control: Option<TimeControl>) -> bool {
+        if self.allowed_time_controls.is_empty() {
+            return true;
+        }
+        time_control.is_some_and(|tc| self.allowed_time_controls.contains(&tc))
+    }
+}
+
+// Per-chain defaults given when the application is created on a chain; every field is optional
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
-pub struct ChessParameters;
+#[serde(default)]
+pub struct InstantiationArgument {
+    pub display_name: Option<String>, // Initial profile name
+    pub settings: ChainSettings,
+}
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct InstantiationArgument;
+// Who may join the matches hosted on this chain without the host's approval
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq, Enum)]
+pub enum AutoAcceptPolicy {
+    #[default]
+    Everyone,
+    Nobody, // Join requests wait for AcceptJoin or DeclineJoin
+}
+
+// Whether games hosted on this chain are published for spectators
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq, Enum)]
+pub enum SpectatorVisibility {
+    #[default]
+    Public,
+    Private,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(input_name = "ChainSettingsInput", rename_fields = "camelCase")]
+#[serde(default)]
+pub struct ChainSettings {
+    pub default_time_control: Option<TimeControl>, // Used when CreateMatch gives none
+    pub auto_accept: AutoAcceptPolicy,
+    pub spectator_visibility: SpectatorVisibility,
+}
+
+// Join request held until the host decides on it
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct PendingJoin {
+    pub player_chain_id: String,
+    pub player_name: String,
+    pub owner: Option<AccountOwner>,
+    pub stake: Option<Amount>,
+    pub requested_at: String,
+}
 
 // Chess piece types
 #[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Enum)]
@@ -133,16 +253,637 @@ impl ChessMove {
 #[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Enum)]
 pub enum MatchStatus {
     WaitingForPlayer,
+    WaitingForReady, // Joined; both players must confirm with SetReady
     Active,
     Ended,
 }
 
+// Time control: base time plus increment per move
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, SimpleObject, InputObject)]
+#[graphql(input_name = "TimeControlInput", rename_fields = "camelCase")]
+pub struct TimeControl {
+    pub base_secs: u32,
+    pub increment_secs: u32,
+}
+
+impl TimeControl {
+    // Categorised by the estimated game length: base time plus 40 increments
+    pub fn category(&self) -> TimeCategory {
+        let estimated_secs = self.base_secs as u64 + 40 * self.increment_secs as u64;
+        if estimated_secs < 180 {
+            TimeCategory::Bullet
+        } else if estimated_secs < 480 {
+            TimeCategory::Blitz
+        } else if estimated_secs < 1500 {
+            TimeCategory::Rapid
+        } else {
+            TimeCategory::Classical
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Enum)]
+pub enum TimeCategory {
+    Bullet,
+    Blitz,
+    Rapid,
+    Classical,
+    Correspondence, // No clock
+}
+
+// Rule set a game is played under
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Enum)]
+pub enum Variant {
+    Standard,
+    Armageddon, // Black has less time but wins on a draw
+}
+
+// Chess clock; the side to move (Game::current_turn) is running since `running_since`
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct Clock {
+    pub time_control: TimeControl,
+    pub white_remaining_micros: u64,
+    pub black_remaining_micros: u64,
+    pub running_since: Option<u64>,
+    pub white_berserk: bool,
+    pub black_berserk: bool,
+}
+
+impl Clock {
+    pub fn new(time_control: TimeControl) -> Self {
+        let base = time_control.base_secs as u64 * 1_000_000;
+        Self {
+            time_control,
+            white_remaining_micros: base,
+            black_remaining_micros: base,
+            running_since: None,
+            white_berserk: false,
+            black_berserk: false,
+        }
+    }
+
+    // Armageddon: Black has draw odds and gets four fifths of White's time
+    pub fn armageddon(time_control: TimeControl) -> Self {
+        let mut clock = Self::new(time_control);
+        clock.black_remaining_micros = clock.white_remaining_micros / 5 * 4;
+        clock
+    }
+
+    pub fn start(&mut self, now: u64) {
+        self.running_since = Some(now);
+    }
+
+    fn stored_remaining(&self, color: Color) -> u64 {
+        match color {
+            Color::White => self.white_remaining_micros,
+            Color::Black => self.black_remaining_micros,
+        }
+    }
+
+    fn set_remaining(&mut self, color: Color, micros: u64) {
+        match color {
+            Color::White => self.white_remaining_micros = micros,
+            Color::Black => self.black_remaining_micros = micros,
+        }
+    }
+
+    pub fn is_berserk(&self, color: Color) -> bool {
+        match color {
+            Color::White => self.white_berserk,
+            Color::Black => self.black_berserk,
+        }
+    }
+
+    // Remaining time of `color` at `now`, counting the running period if it is their turn
+    pub fn remaining(&self, color: Color, turn: Color, now: u64) -> u64 {
+        let stored = self.stored_remaining(color);
+        match self.running_since {
+            Some(since) if color == turn => stored.saturating_sub(now.saturating_sub(since)),
+            _ => stored,
+        }
+    }
+
+    // Remaining time of `color` if their clock has been running since `since`
+    pub fn remaining_since(&self, color: Color, since: u64, now: u64) -> u64 {
+        self.stored_remaining(color).saturating_sub(now.saturating_sub(since))
+    }
+
+    // Stop the mover's clock after a move; returns their remaining time, or None if they flagged
+    pub fn punch(&mut self, mover: Color, now: u64) -> Option<u64> {
+        let remaining = self.remaining(mover, mover, now);
+        if remaining == 0 {
+            return None;
+        }
+        let increment = if self.is_berserk(mover) {
+            0
+        } else {
+            self.time_control.increment_secs as u64 * 1_000_000
+        };
+        let remaining = remaining + increment;
+        self.set_remaining(mover, remaining);
+        self.running_since = Some(now);
+        Some(remaining)
+    }
+
+    // Apply the remaining time reported by the opponent's chain and start our own clock.
+    // The report is capped at what our own clock gives the mover, so they cannot add time;
+    // returns the remaining time applied.
+    pub fn sync_after_move(&mut self, mover: Color, reported: u64, now: u64) -> u64 {
+        let increment = if self.is_berserk(mover) {
+            0
+        } else {
+            self.time_control.increment_secs as u64 * 1_000_000
+        };
+        let local = self.remaining(mover, mover, now) + increment;
+        let remaining = reported.min(local);
+        self.set_remaining(mover, remaining);
+        self.running_since = Some(now);
+        remaining
+    }
+
+    // Berserk halves the player's own clock and removes their increment
+    pub fn berserk(&mut self, color: Color) {
+        let halved = self.time_control.base_secs as u64 * 1_000_000 / 2;
+        self.set_remaining(color, halved);
+        match color {
+            Color::White => self.white_berserk = true,
+            Color::Black => self.black_berserk = true,
+        }
+    }
+
+    // Restore both clocks from what the remaining move records say
+    pub fn restore(&mut self, move_history: &[MoveRecord], now: u64) {
+        for color in [Color::White, Color::Black] {
+            let remaining = move_history
+                .iter()
+                .rev()
+                .find(|m| m.player_color == color)
+                .and_then(|m| m.clock_remaining_micros)
+                .unwrap_or_else(|| {
+                    if self.is_berserk(color) {
+                        self.time_control.base_secs as u64 * 1_000_000 / 2
+                    } else {
+                        self.time_control.base_secs as u64 * 1_000_000
+                    }
+                });
+            self.set_remaining(color, remaining);
+        }
+        self.running_since = Some(now);
+    }
+}
+
+// Why a game ended
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Enum)]
+pub enum Termination {
+    Checkmate,
+    Stalemate,
+    InsufficientMaterial,
+    Resignation,
+    Timeout,
+    Abandonment,
+    Agreement,
+    Aborted, // Cancelled or aborted before it really started; unrated
+}
+
+// Default time a player may stay silent on their turn before the opponent can claim abandonment
+pub const DEFAULT_INACTIVITY_LIMIT_SECS: u64 = 24 * 60 * 60;
+
+// Time both players have to confirm they are ready once a match is joined
+pub const READY_TIMEOUT_SECS: u64 = 5 * 60;
+
+// How a multi-game series is decided
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Enum)]
+pub enum SeriesKind {
+    BestOf,  // target = number of games
+    FirstTo, // target = points needed
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, SimpleObject, InputObject)]
+#[graphql(input_name = "SeriesFormatInput", rename_fields = "camelCase")]
+pub struct SeriesFormat {
+    pub kind: SeriesKind,
+    pub target: u32,
+    pub armageddon_tiebreak: bool,
+    pub tiebreak_games: Option<u32>, // BestOf only: extra games played before Armageddon
+}
+
+// Stage of a series the next game belongs to
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Enum)]
+pub enum SeriesPhase {
+    Regular,
+    Tiebreak,
+    Armageddon,
+}
+
+// Score of one player in a series, in half points so draws stay exact
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct SeriesScore {
+    pub chain_id: String,
+    pub half_points: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct SeriesGameResult {
+    pub match_id: String,
+    pub white_chain_id: String,
+    pub black_chain_id: String,
+    pub winner_chain_id: Option<String>,
+    pub armageddon: bool,
+}
+
+// Match series (rematches between the same two chains)
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct MatchSeries {
+    pub series_id: String,
+    pub format: SeriesFormat,
+    pub scores: Vec<SeriesScore>,
+    pub results: Vec<SeriesGameResult>,
+    pub finished: bool,
+    pub winner_chain_id: Option<String>,
+}
+
+impl MatchSeries {
+    pub fn new(series_id: String, format: SeriesFormat, host_chain_id: &str, guest_chain_id: &str) -> Self {
+        Self {
+            series_id,
+            format,
+            scores: vec![
+                SeriesScore { chain_id: host_chain_id.to_string(), half_points: 0 },
+                SeriesScore { chain_id: guest_chain_id.to_string(), half_points: 0 },
+            ],
+            results: Vec::new(),
+            finished: false,
+            winner_chain_id: None,
+        }
+    }
+
+    pub fn half_points(&self, chain_id: &str) -> u32 {
+        self.scores
+            .iter()
+            .find(|s| s.chain_id == chain_id)
+            .map(|s| s.half_points)
+            .unwrap_or(0)
+    }
+
+    // The next game is an Armageddon decider when regular and tiebreak games are exhausted with a tie
+    pub fn needs_armageddon(&self) -> bool {
+        self.next_phase() == Some(SeriesPhase::Armageddon)
+    }
+
+    // Phase of the next game, or None if the series is over
+    pub fn next_phase(&self) -> Option<SeriesPhase> {
+        if self.finished {
+            return None;
+        }
+        let regular_games = self.results.iter().filter(|r| !r.armageddon).count() as u32;
+        let tied = self.scores.iter().all(|s| s.half_points == self.scores[0].half_points);
+        let exhausted = match self.format.kind {
+            SeriesKind::BestOf => {
+                let tiebreak_games = self.format.tiebreak_games.unwrap_or(0);
+                if regular_games >= self.format.target && regular_games < self.format.target + tiebreak_games {
+                    return Some(SeriesPhase::Tiebreak);
+                }
+                regular_games >= self.format.target + tiebreak_games
+            }
+            SeriesKind::FirstTo => tied && self.scores[0].half_points >= self.format.target * 2,
+        };
+        if exhausted && tied && self.format.armageddon_tiebreak {
+            Some(SeriesPhase::Armageddon)
+        } else {
+            Some(SeriesPhase::Regular)
+        }
+    }
+
+    // Record a finished game; recording the same match twice is a no-op
+    pub fn record(&mut self, result: SeriesGameResult) {
+        if self.finished || self.results.iter().any(|r| r.match_id == result.match_id) {
+            return;
+        }
+
+        if result.armageddon {
+            // Draw odds: Black wins the series on a drawn Armageddon game
+            let winner = result
+                .winner_chain_id
+                .clone()
+                .unwrap_or_else(|| result.black_chain_id.clone());
+            self.add_half_points(&winner, 2);
+            self.results.push(result);
+            self.finished = true;
+            self.winner_chain_id = Some(winner);
+            return;
+        }
+
+        match &result.winner_chain_id {
+            Some(winner) => self.add_half_points(winner, 2),
+            None => {
+                let white = result.white_chain_id.clone();
+                let black = result.black_chain_id.clone();
+                self.add_half_points(&white, 1);
+                self.add_half_points(&black, 1);
+            }
+        }
+        self.results.push(result);
+        self.update_outcome();
+    }
+
+    fn add_half_points(&mut self, chain_id: &str, half_points: u32) {
+        if let Some(score) = self.scores.iter_mut().find(|s| s.chain_id == chain_id) {
+            score.half_points += half_points;
+        }
+    }
+
+    // Leader over a slice of games, in half points
+    fn leader_of(&self, games: &[SeriesGameResult]) -> (Option<String>, u32) {
+        let points = |chain_id: &str| -> u32 {
+            games
+                .iter()
+                .map(|r| match &r.winner_chain_id {
+                    Some(winner) if winner == chain_id => 2,
+                    Some(_) => 0,
+                    None => 1,
+                })
+                .sum()
+        };
+        let a = points(&self.scores[0].chain_id);
+        let b = points(&self.scores[1].chain_id);
+        let leader = if a > b {
+            Some(self.scores[0].chain_id.clone())
+        } else if b > a {
+            Some(self.scores[1].chain_id.clone())
+        } else {
+            None
+        };
+        (leader, a.max(b))
+    }
+
+    fn update_outcome(&mut self) {
+        match self.format.kind {
+            SeriesKind::BestOf => {
+                // Regular games first, then each tiebreak phase is decided on its own games
+                let regular: Vec<SeriesGameResult> =
+                    self.results.iter().filter(|r| !r.armageddon).cloned().collect();
+                let target = self.format.target as usize;
+                let tiebreak_games = self.format.tiebreak_games.unwrap_or(0) as usize;
+                for (start, length) in [(0, target), (target, tiebreak_games)] {
+                    if length == 0 {
+                        continue;
+                    }
+                    let end = (start + length).min(regular.len());
+                    if start >= end {
+                        return;
+                    }
+                    let (leader, top) = self.leader_of(&regular[start..end]);
+                    // A clear majority of the scheduled games decides early
+                    if top > length as u32 || (end - start == length && leader.is_some()) {
+                        self.finished = true;
+                        self.winner_chain_id = leader;
+                        return;
+                    }
+                    if end - start < length {
+                        return;
+                    }
+                }
+                // Still tied once every scheduled game is played
+                self.finished = !self.format.armageddon_tiebreak;
+                self.winner_chain_id = None;
+            }
+            SeriesKind::FirstTo => {
+                let (a, b) = (&self.scores[0], &self.scores[1]);
+                let leader = if a.half_points > b.half_points {
+                    Some(a.chain_id.clone())
+                } else if b.half_points > a.half_points {
+                    Some(b.chain_id.clone())
+                } else {
+                    None
+                };
+                if a.half_points.max(b.half_points) >= self.format.target * 2 {
+                    self.finished = leader.is_some() || !self.format.armageddon_tiebreak;
+                    self.winner_chain_id = leader;
+                }
+            }
+        }
+    }
+}
+
 // Player info
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
 #[graphql(rename_fields = "camelCase")]
 pub struct PlayerInfo {
     pub chain_id: String,
     pub name: String,
+    pub owner: Option<AccountOwner>, // Identifies the player; `chain_id` is where to reach them
+}
+
+// Abandonment count for one player chain
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct AbandonmentRecord {
+    pub chain_id: String,
+    pub count: u32,
+}
+
+// What an inbox notification is about; every incoming cross-chain message produces one
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Enum)]
+pub enum NotificationKind {
+    PlayerJoined,
+    MatchReady,
+    OpponentReady,
+    GameSynced,
+    ResyncRequested,
+    OpponentMoved,
+    LostOnTime,
+    OpponentBerserk,
+    OpponentResigned,
+    GameEnded,
+    OpponentAborted,
+    TakebackRequested,
+    TakebackAccepted,
+    GameForfeited,
+    DrawOffered,
+    DrawAgreed,
+    RematchOffered,
+    RematchStarted,
+    TournamentRegistration,
+    TournamentGameStarted,
+    TournamentGameReported,
+    TournamentGameForfeited,
+    StakeReleased,
+    TournamentSponsored,
+    JoinRequested,
+    JoinDeclined,
+    CollectibleReceived,
+}
+
+impl NotificationKind {
+    pub fn message(&self) -> &'static str {
+        match self {
+            NotificationKind::PlayerJoined => "Player joined",
+            NotificationKind::MatchReady => "Match ready",
+            NotificationKind::OpponentReady => "Opponent is ready",
+            NotificationKind::GameSynced => "Game synchronized",
+            NotificationKind::ResyncRequested => "Opponent requested a resync",
+            NotificationKind::OpponentMoved => "Opponent moved",
+            NotificationKind::LostOnTime => "Lost on time",
+            NotificationKind::OpponentBerserk => "Opponent went berserk",
+            NotificationKind::OpponentResigned => "Opponent resigned",
+            NotificationKind::GameEnded => "Game ended",
+            NotificationKind::OpponentAborted => "Opponent aborted the game",
+            NotificationKind::TakebackRequested => "Takeback requested",
+            NotificationKind::TakebackAccepted => "Takeback accepted",
+            NotificationKind::GameForfeited => "Game forfeited by abandonment",
+            NotificationKind::DrawOffered => "Draw offered",
+            NotificationKind::DrawAgreed => "Draw agreed",
+            NotificationKind::RematchOffered => "Rematch offered",
+            NotificationKind::RematchStarted => "Rematch started",
+            NotificationKind::TournamentRegistration => "Player registered for tournament",
+            NotificationKind::TournamentGameStarted => "Tournament game started",
+            NotificationKind::TournamentGameReported => "Tournament game reported",
+            NotificationKind::TournamentGameForfeited => "Tournament game forfeited by a busy player",
+            NotificationKind::StakeReleased => "Stake released from escrow",
+            NotificationKind::TournamentSponsored => "Tournament prize pool sponsored",
+            NotificationKind::JoinRequested => "Player asked to join",
+            NotificationKind::JoinDeclined => "Join request declined",
+            NotificationKind::CollectibleReceived => "Collectible received",
+        }
+    }
+}
+
+// Inbox entry; the inbox keeps the newest MAX_NOTIFICATIONS entries
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct Notification {
+    pub id: u64,
+    pub kind: NotificationKind,
+    pub message: String,
+    pub match_id: Option<String>,
+    pub counterparty_chain_id: Option<String>,
+    pub timestamp: String,
+    pub read: bool,
+}
+
+pub const MAX_NOTIFICATIONS: usize = 50;
+
+// Why a move from the opponent's chain did not fit our copy of the game
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Enum)]
+pub enum DivergenceKind {
+    StaleMove,         // A different move than ours at an earlier ply
+    PlyGap,            // Moves are missing between our history and the reported ply
+    WrongTurn,
+    IllegalMove,
+    ReconstructFailed, // Our own history does not replay
+    HashMismatch,      // Same move, different resulting position
+}
+
+// Detected mismatch between the two players' copies; resolved by the host's GameSync
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct Divergence {
+    pub match_id: String,
+    pub ply: u32,
+    pub local_ply: u32,
+    pub kind: DivergenceKind,
+    pub remote_hash: Option<String>, // Hex Zobrist hashes
+    pub local_hash: Option<String>,
+    pub detected_at: String,
+    pub resolved: bool,
+}
+
+pub const MAX_DIVERGENCES: usize = 50;
+
+// Stakes of a wagered game, held by the application on the host's chain, where neither
+// player can take theirs back: the host's is locked when the match is created and the
+// guest's is sent along with the join request. When the game ends the winner collects both;
+// draws and aborted games (cancelled, aborted early, ready timeout) refund each stake.
+// Abandonment and timeout claims are wins like any other.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct Escrow {
+    pub match_id: String,
+    pub stakes: Vec<Stake>, // The host's first
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct Stake {
+    pub chain_id: String,
+    pub owner: AccountOwner,
+    pub amount: Amount,
+}
+
+// Opponent move as received from their chain
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct SequencedMove {
+    pub ply: u32,
+    pub chess_move: ChessMove,
+    pub player_chain_id: String,
+    pub clock_remaining_micros: Option<u64>,
+    pub position_hash: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SequenceOutcome {
+    Apply,     // The move is the expected next ply
+    Duplicate, // Already applied or already buffered
+    Buffered,  // Arrived early; kept until the missing plies are in
+    Gap,       // Too far ahead to wait for; resync instead
+}
+
+// Early moves waiting for the missing plies before them
+pub const MAX_BUFFERED_MOVES: usize = 8;
+
+// Puts the opponent's moves of one game in ply order
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct MoveSequencer {
+    pub match_id: String,
+    pub expected_ply: u32,
+    pub buffered: Vec<SequencedMove>, // Sorted by ply
+}
+
+impl MoveSequencer {
+    pub fn new(match_id: &str) -> Self {
+        Self {
+            match_id: match_id.to_string(),
+            expected_ply: 1,
+            buffered: Vec::new(),
+        }
+    }
+
+    // Classify an incoming move; `expected_ply` is the next ply our history is missing
+    pub fn receive(&mut self, incoming: SequencedMove, expected_ply: u32) -> SequenceOutcome {
+        self.expected_ply = expected_ply;
+        self.buffered.retain(|m| m.ply >= expected_ply);
+        if incoming.ply < expected_ply || self.buffered.iter().any(|m| m.ply == incoming.ply) {
+            return SequenceOutcome::Duplicate;
+        }
+        if incoming.ply == expected_ply {
+            return SequenceOutcome::Apply;
+        }
+        if self.buffered.len() >= MAX_BUFFERED_MOVES
+            || incoming.ply - expected_ply > 2 * MAX_BUFFERED_MOVES as u32
+        {
+            self.buffered.clear();
+            return SequenceOutcome::Gap;
+        }
+        let index = self.buffered.partition_point(|m| m.ply < incoming.ply);
+        self.buffered.insert(index, incoming);
+        SequenceOutcome::Buffered
+    }
+
+    // Take the buffered move for `expected_ply`, if it has arrived; stale entries are dropped
+    pub fn take_next(&mut self, expected_ply: u32) -> Option<SequencedMove> {
+        self.expected_ply = expected_ply;
+        self.buffered.retain(|m| m.ply >= expected_ply);
+        match self.buffered.first() {
+            Some(m) if m.ply == expected_ply => Some(self.buffered.remove(0)),
+            _ => None,
+        }
+    }
 }
 
 // Move record (similar to RoundRecord in SPS)
@@ -154,6 +895,51 @@ pub struct MoveRecord {
     pub player_color: Color,
     pub timestamp: String,
     pub fen_after: String,
+    pub clock_remaining_micros: Option<u64>,
+}
+
+// Links a game to the tournament board it was paired on
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct TournamentGameRef {
+    pub tournament_id: String,
+    pub organizer_chain_id: String,
+    pub round: u32,
+    pub board: u32,
+    pub game_number: u32,
+    pub arena: bool,
+}
+
+// Pending takeback: undo `plies` half-moves, valid only while the history still has `at_ply` moves
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct TakebackRequest {
+    pub requested_by: String,
+    pub plies: u32,
+    pub at_ply: u32,
+}
+
+// Outcome of a game as exposed to other applications
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct GameResult {
+    pub match_id: String,
+    pub host_chain_id: String,
+    pub status: MatchStatus,
+    pub players: Vec<PlayerInfo>, // White first
+    pub winner_chain_id: Option<String>, // None for draws and aborted games
+    pub termination: Option<Termination>,
+    pub variant: Variant,
+    pub rated: bool,
+    pub move_count: u32,
+    pub final_fen: String,
+}
+
+// Application on this chain waiting for the end of a match, counted against `MAX_RESULT_WATCHERS`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultWatcher {
+    pub match_id: String,
+    pub application_id: ApplicationId,
 }
 
 // Game structure (single game per chain, like SPS)
@@ -168,27 +954,514 @@ pub struct Game {
     pub board: String, // FEN notation
     pub move_history: Vec<MoveRecord>,
     pub created_at: String,
+    pub started_at: Option<String>,
+    pub ready_deadline_micros: Option<u64>,
     pub last_move_at: Option<String>,
     pub winner_chain_id: Option<String>,
+    pub termination: Option<Termination>,
+    pub rated: bool,
+    pub inactivity_limit_secs: u64,
+    pub takeback_request: Option<TakebackRequest>,
+    pub clock: Option<Clock>,
+    pub series: Option<MatchSeries>,
+    pub tournament: Option<TournamentGameRef>,
+    pub armageddon: bool,
+    pub rematch_offered_by: Option<String>,
+    pub draw_offered_by: Option<String>,
+    pub shared_chain: bool, // Played on a chain owned by both players instead of synced copies
+    pub game_chain_id: Option<String>, // That chain, once the host has opened it
+    pub wager: Option<Amount>, // Stake each player puts in escrow on the host's chain; the winner collects both
+    pub spectators_allowed: bool, // Whether the game is published on the host's event stream
+    pub engine_level: Option<EngineLevel>, // Single-player game: the engine plays Black
+}
+
+impl Game {
+    pub fn is_aborted(&self) -> bool {
+        self.termination == Some(Termination::Aborted)
+    }
+
+    // The host always plays White
+    pub fn color_of(&self, chain_id: &str) -> Option<Color> {
+        if self.host_chain_id == chain_id {
+            Some(Color::White)
+        } else if self.players.iter().any(|p| p.chain_id == chain_id) {
+            Some(Color::Black)
+        } else {
+            None
+        }
+    }
+
+    pub fn player_with_color(&self, color: Color) -> Option<&PlayerInfo> {
+        self.players
+            .iter()
+            .find(|p| self.color_of(&p.chain_id) == Some(color))
+    }
+
+    // FEN after `ply` moves (0 is the starting position) and the move that led to it
+    pub fn position_at(&self, ply: u32) -> Option<(String, Option<ChessMove>)> {
+        if ply == 0 {
+            return Some((STARTING_FEN.to_string(), None));
+        }
+        self.move_history
+            .get(ply as usize - 1)
+            .map(|record| (record.fen_after.clone(), Some(record.chess_move.clone())))
+    }
+
+    // Summary given to other applications; `status` tells whether the game is over
+    pub fn result(&self) -> GameResult {
+        GameResult {
+            match_id: self.match_id.clone(),
+            host_chain_id: self.host_chain_id.clone(),
+            status: self.status,
+            players: self.players.clone(),
+            winner_chain_id: self.winner_chain_id.clone(),
+            termination: self.termination,
+            variant: self.variant(),
+            rated: self.rated,
+            move_count: self.move_history.len() as u32,
+            final_fen: self.board.clone(),
+        }
+    }
+
+    pub fn variant(&self) -> Variant {
+        if self.armageddon {
+            Variant::Armageddon
+        } else {
+            Variant::Standard
+        }
+    }
+
+    pub fn time_category(&self) -> TimeCategory {
+        self.clock
+            .as_ref()
+            .map(|c| c.time_control.category())
+            .unwrap_or(TimeCategory::Correspondence)
+    }
+
+    // Result of an ended game for one of its players; aborted games have none
+    pub fn outcome_for(&self, chain_id: &str) -> Option<GameOutcome> {
+        if self.status != MatchStatus::Ended || self.is_aborted() || self.color_of(chain_id).is_none() {
+            return None;
+        }
+        Some(match self.winner_chain_id.as_deref() {
+            Some(winner) if winner == chain_id => GameOutcome::Win,
+            Some(_) => GameOutcome::Loss,
+            None => GameOutcome::Draw,
+        })
+    }
+
+    // Whether `chain_id` is the shared chain holding the live copy of this game
+    pub fn is_game_chain(&self, chain_id: &str) -> bool {
+        self.game_chain_id.as_deref() == Some(chain_id)
+    }
+
+    pub fn player_by_owner(&self, owner: &AccountOwner) -> Option<&PlayerInfo> {
+        self.players.iter().find(|p| p.owner.as_ref() == Some(owner))
+    }
+
+    pub fn color_of_owner(&self, owner: &AccountOwner) -> Option<Color> {
+        self.player_by_owner(owner).and_then(|p| self.color_of(&p.chain_id))
+    }
+
+    pub fn opponent_of(&self, chain_id: &str) -> Option<&PlayerInfo> {
+        self.players.iter().find(|p| p.chain_id != chain_id)
+    }
+
+    // Micros of the last move, or of the start (or creation) if nobody has moved yet
+    pub fn last_activity_micros(&self) -> u64 {
+        self.last_move_at
+            .as_deref()
+            .or(self.started_at.as_deref())
+            .unwrap_or(&self.created_at)
+            .parse()
+            .unwrap_or(0)
+    }
+
+    // A move declines any pending draw offer from the other side
+    pub fn clear_declined_draw_offer(&mut self, mover: Color) {
+        let offered_by_mover = self
+            .draw_offered_by
+            .as_deref()
+            .map(|chain_id| self.color_of(chain_id) == Some(mover))
+            .unwrap_or(false);
+        if !offered_by_mover {
+            self.draw_offered_by = None;
+        }
+    }
+
+    // Update a read-only mirror of a game from the host's event stream
+    pub fn apply_event(&mut self, event: &ChessEvent) {
+        match event {
+            ChessEvent::GameStarted { game } => *self = (**game).clone(),
+            ChessEvent::MovePlayed { move_record, .. } => {
+                if move_record.move_number as usize != self.move_history.len() + 1 {
+                    return; // Already applied
+                }
+                self.move_history.push(move_record.clone());
+                self.board = move_record.fen_after.clone();
+                self.current_turn = match move_record.player_color {
+                    Color::White => Color::Black,
+                    Color::Black => Color::White,
+                };
+                self.last_move_at = Some(move_record.timestamp.clone());
+                self.takeback_request = None;
+                self.clear_declined_draw_offer(move_record.player_color);
+            }
+            ChessEvent::PlayersReady { started_at, .. } => {
+                self.status = MatchStatus::Active;
+                self.started_at = Some(started_at.to_string());
+                self.ready_deadline_micros = None;
+                if let Some(clock) = self.clock.as_mut() {
+                    clock.start(*started_at);
+                }
+            }
+            ChessEvent::DrawOffered { offered_by, .. } => {
+                self.draw_offered_by = Some(offered_by.clone());
+            }
+            ChessEvent::TakebackRequested { request, .. } => {
+                self.takeback_request = Some(request.clone());
+            }
+            ChessEvent::TakebackApplied { moves_left, .. } => {
+                self.move_history.truncate(*moves_left as usize);
+                let last = self.move_history.last();
+                self.board = last
+                    .map(|m| m.fen_after.clone())
+                    .unwrap_or_else(|| STARTING_FEN.to_string());
+                self.current_turn = match last.map(|m| m.player_color) {
+                    Some(Color::White) => Color::Black,
+                    _ => Color::White,
+                };
+                self.last_move_at = last.map(|m| m.timestamp.clone());
+                self.takeback_request = None;
+            }
+            ChessEvent::GameEnded {
+                winner_chain_id,
+                termination,
+                ..
+            } => {
+                self.status = MatchStatus::Ended;
+                self.winner_chain_id = winner_chain_id.clone();
+                self.termination = *termination;
+                self.draw_offered_by = None;
+            }
+            ChessEvent::ResultRecorded { .. } => {}
+        }
+    }
+}
+
+pub const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+// Events the host chain publishes on a game's stream, so that other chains can spectate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ChessEvent {
+    GameStarted { game: Box<Game> },
+    PlayersReady { match_id: String, started_at: u64 },
+    MovePlayed { match_id: String, san: String, move_record: MoveRecord },
+    DrawOffered { match_id: String, offered_by: String },
+    TakebackRequested { match_id: String, request: TakebackRequest },
+    TakebackApplied { match_id: String, moves_left: u32 },
+    GameEnded { match_id: String, winner_chain_id: Option<String>, termination: Option<Termination> },
+    ResultRecorded { result: GameResult }, // Only on the results stream
+}
+
+impl ChessEvent {
+    pub fn match_id(&self) -> &str {
+        match self {
+            ChessEvent::GameStarted { game } => &game.match_id,
+            ChessEvent::PlayersReady { match_id, .. }
+            | ChessEvent::MovePlayed { match_id, .. }
+            | ChessEvent::DrawOffered { match_id, .. }
+            | ChessEvent::TakebackRequested { match_id, .. }
+            | ChessEvent::TakebackApplied { match_id, .. }
+            | ChessEvent::GameEnded { match_id, .. } => match_id,
+            ChessEvent::ResultRecorded { result } => &result.match_id,
+        }
+    }
+
+    pub fn kind(&self) -> GameUpdateKind {
+        match self {
+            ChessEvent::GameStarted { .. } => GameUpdateKind::GameStarted,
+            ChessEvent::PlayersReady { .. } => GameUpdateKind::PlayersReady,
+            ChessEvent::MovePlayed { .. } => GameUpdateKind::MovePlayed,
+            ChessEvent::DrawOffered { .. } => GameUpdateKind::DrawOffered,
+            ChessEvent::TakebackRequested { .. } => GameUpdateKind::TakebackRequested,
+            ChessEvent::TakebackApplied { .. } => GameUpdateKind::TakebackApplied,
+            ChessEvent::GameEnded { .. } | ChessEvent::ResultRecorded { .. } => GameUpdateKind::GameEnded,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Enum)]
+pub enum GameUpdateKind {
+    GameStarted,
+    PlayersReady,
+    MovePlayed,
+    DrawOffered,
+    TakebackRequested,
+    TakebackApplied,
+    GameEnded,
+}
+
+// Entry of the chain's update log. Clients listen to the node's block notifications for the
+// chain and then fetch only the updates after the last sequence number they have seen.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct GameUpdate {
+    pub sequence: u64,
+    pub match_id: String,
+    pub kind: GameUpdateKind,
+    pub status: MatchStatus,
+    pub ply: u32,
+    pub fen: String,
+    pub san: Option<String>,
+    pub actor_chain_id: Option<String>,
+    pub timestamp: String,
+}
+
+// One stream per game, on the host's chain
+pub fn game_stream_name(match_id: &str) -> StreamName {
+    StreamName::from(format!("game-{}", match_id))
 }
 
+// Results of every match archived on a chain, for applications that subscribe to them
+pub fn results_stream_name() -> StreamName {
+    StreamName::from("results")
+}
+
+// Most matches a chain follows at once on behalf of other applications
+pub const MAX_RESULT_WATCHERS: usize = 100;
+
 // Operation types
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Operation {
-    CreateMatch { host_name: String },
-    JoinMatch { host_chain_id: String, player_name: String },
+    CreateMatch {
+        host_name: String,
+        series: Option<SeriesFormat>,
+        rated: bool,
+        inactivity_limit_secs: Option<u64>,
+        time_control: Option<TimeControl>,
+        shared_chain: bool,
+        wager: Option<Amount>,
+    },
+    // Practice game against the built-in engine, which answers each move in the same block
+    PlayEngine { player_name: String, level: EngineLevel },
+    // A staked match is joined by locking the same `wager`
+    JoinMatch { host_chain_id: String, player_name: String, wager: Option<Amount> },
     MakeMove { chess_move: ChessMove },
+    ClaimTimeout,
+    Berserk,
     ResignMatch,
     EndGame { status: MatchStatus },
+    OfferRematch,
+    AcceptRematch,
+    CancelMatch,
+    AbortGame,
+    RequestTakeback,
+    AcceptTakeback,
+    ClaimAbandonment, // Also ends an expired ready handshake: an abort, blamed on the opponent if only we confirmed
+    SetReady,
+    RequestResync,
+    OfferDraw,
+    AcceptDraw,
+    MarkNotificationsRead { ids: Option<Vec<u64>> }, // None marks everything read
+    // `None` keeps a field, an empty string clears it
+    UpdateProfile {
+        display_name: Option<String>,
+        avatar_hash: Option<String>,
+        country: Option<String>,
+        bio: Option<String>,
+        preferred_time_controls: Option<Vec<TimeControl>>,
+    },
+    UpdateSettings { settings: ChainSettings },
+    AcceptJoin { player_chain_id: String }, // Declines the other pending requests
+    DeclineJoin { player_chain_id: String },
+    WatchGame { host_chain_id: String, match_id: String },
+    // Mint the current game, once it has ended, as a collectible held by the signer
+    MintCollectible,
+    // `recipient_owner` becomes the only signer allowed to transfer it further
+    TransferCollectible {
+        collectible_id: String,
+        recipient_chain_id: String,
+        recipient_owner: Option<AccountOwner>,
+    },
+    // Called by other applications on this chain; answered with `ChessResponse::Result`
+    QueryResult { match_id: String },
+    // Called by another application to have the result published on `results_stream_name()` when
+    // the match ends; games hosted elsewhere are followed through the host's event stream
+    WatchResult { host_chain_id: Option<String>, match_id: String },
+    UnwatchGame { host_chain_id: String, match_id: String },
+    CreateTournament {
+        name: String,
+        format: TournamentFormat,
+        rounds: Option<u32>,
+        time_control: Option<TimeControl>,
+        duration_minutes: Option<u32>,
+        mini_match: Option<SeriesFormat>,
+        entry_fee: Option<Amount>,
+        payout_percentages: Option<Vec<u32>>, // Defaults to 50/30/20
+    },
+    // `entry_fee` must match the tournament's and is paid from the signer's account
+    RegisterForTournament {
+        organizer_chain_id: String,
+        tournament_id: String,
+        player_name: String,
+        entry_fee: Option<Amount>,
+    },
+    SponsorTournament { organizer_chain_id: String, tournament_id: String, amount: Amount },
+    StartTournament { tournament_id: String },
+    // Finish an arena after its end time and pay out its prize pool
+    CloseTournament { tournament_id: String },
+    // Call off a tournament that has not finished and refund its prize pool
+    CancelTournament { tournament_id: String },
 }
 
 // Cross-chain message types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum CrossChainMessage {
-    JoinRequest { player_chain_id: ChainId, player_name: String },
+    JoinRequest {
+        player_chain_id: ChainId,
+        player_name: String,
+        owner: Option<AccountOwner>,
+        stake: Option<Amount>, // Already sent to the application's account on the host's chain
+    },
+    JoinDeclined { host_chain_id: ChainId }, // The host has refunded the guest's stake, if any
+    // Returned unchanged to the sender if it bounces
+    CollectibleTransfer { collectible: Collectible, recipient_owner: Option<AccountOwner> },
     InitialStateSync { game: Game },
+    HostGame { game: Game }, // Sent by the host to a new shared game chain, which takes over the game
+    GameChainResult { game: Game }, // Final state sent by a shared game chain to both players before it closes
+    GameChainEvent { event: ChessEvent }, // Relayed by a shared game chain for the players' chains to publish
+    GameChainAbandonment { chain_id: String }, // Abandonment counted by a shared game chain against a player
+    ReadyNotice { player_chain_id: ChainId, match_id: String },
     GameSync { game: Game },
-    MoveSync { chess_move: ChessMove, player_chain_id: ChainId },
+    // `ply` is the move number this move creates, `position_hash` the Zobrist hash after it
+    MoveSync {
+        chess_move: ChessMove,
+        player_chain_id: ChainId,
+        clock_remaining_micros: Option<u64>,
+        ply: u32,
+        position_hash: u64,
+    },
+    ResyncRequest { player_chain_id: ChainId, ply: u32 }, // Answered by the host with GameSync
+    TimeoutNotice { player_chain_id: ChainId },
+    BerserkNotice { player_chain_id: ChainId },
     ResignNotice { player_chain_id: ChainId },
     GameEndNotice { player_chain_id: ChainId, status: MatchStatus },
+    RematchOffer { player_chain_id: ChainId },
+    RematchAccepted { player_chain_id: ChainId },
+    AbortNotice { player_chain_id: ChainId },
+    TakebackRequested { player_chain_id: ChainId, plies: u32, at_ply: u32 },
+    TakebackAccepted { player_chain_id: ChainId, plies: u32, at_ply: u32 },
+    AbandonmentClaimed { player_chain_id: ChainId, aborted: bool },
+    DrawOffer { player_chain_id: ChainId },
+    DrawAccepted { player_chain_id: ChainId },
+    TournamentRegistration {
+        tournament_id: String,
+        player_chain_id: ChainId,
+        player_name: String,
+        owner: Option<AccountOwner>,
+        entry_fee: Option<Amount>, // Already sent to the application's account on the organizer's chain
+    },
+    TournamentSponsorship {
+        tournament_id: String,
+        sponsor_chain_id: ChainId,
+        owner: AccountOwner,
+        amount: Amount, // Already sent to the application's account on the organizer's chain
+    },
+    TournamentGameStart {
+        tournament: TournamentGameRef,
+        white: PlayerInfo,
+        black: PlayerInfo,
+        time_control: Option<TimeControl>,
+        armageddon: bool,
+    },
+    TournamentGameResult {
+        tournament_id: String,
+        round: u32,
+        board: u32,
+        game_number: u32,
+        match_id: String,
+        winner_chain_id: Option<String>,
+        white_berserk: bool,
+        black_berserk: bool,
+    },
+    // Sent to the organizer by a player whose other game is unfinished, so the board does not
+    // stall; the game counts as a loss for that player. If Black forfeits, the organizer
+    // passes it on to White, who has already started the game.
+    TournamentGameForfeit { tournament: TournamentGameRef, player_chain_id: ChainId },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequenced(ply: u32) -> SequencedMove {
+        SequencedMove {
+            ply,
+            chess_move: ChessMove {
+                from: Square::new(4, 1),
+                to: Square::new(4, 3),
+                promotion: None,
+            },
+            player_chain_id: "opponent".to_string(),
+            clock_remaining_micros: None,
+            position_hash: ply as u64,
+        }
+    }
+
+    #[test]
+    fn sequencer_applies_moves_in_order() {
+        let mut sequencer = MoveSequencer::new("match");
+        for ply in 1..=4 {
+            assert_eq!(sequencer.receive(sequenced(ply), ply), SequenceOutcome::Apply);
+            assert!(sequencer.take_next(ply + 1).is_none());
+        }
+        assert!(sequencer.buffered.is_empty());
+    }
+
+    #[test]
+    fn sequencer_drops_duplicates() {
+        let mut sequencer = MoveSequencer::new("match");
+        assert_eq!(sequencer.receive(sequenced(1), 1), SequenceOutcome::Apply);
+        // Already applied
+        assert_eq!(sequencer.receive(sequenced(1), 2), SequenceOutcome::Duplicate);
+        // Already buffered
+        assert_eq!(sequencer.receive(sequenced(4), 2), SequenceOutcome::Buffered);
+        assert_eq!(sequencer.receive(sequenced(4), 2), SequenceOutcome::Duplicate);
+        assert_eq!(sequencer.buffered.len(), 1);
+    }
+
+    #[test]
+    fn sequencer_buffers_early_moves_then_drains_them() {
+        let mut sequencer = MoveSequencer::new("match");
+        assert_eq!(sequencer.receive(sequenced(4), 2), SequenceOutcome::Buffered);
+        assert_eq!(sequencer.receive(sequenced(3), 2), SequenceOutcome::Buffered);
+        assert!(sequencer.take_next(2).is_none());
+
+        assert_eq!(sequencer.receive(sequenced(2), 2), SequenceOutcome::Apply);
+        assert_eq!(sequencer.take_next(3).map(|m| m.ply), Some(3));
+        assert_eq!(sequencer.take_next(4).map(|m| m.ply), Some(4));
+        assert!(sequencer.take_next(5).is_none());
+        assert!(sequencer.buffered.is_empty());
+    }
+
+    #[test]
+    fn sequencer_gives_up_when_the_buffer_overflows() {
+        let mut sequencer = MoveSequencer::new("match");
+        for ply in 2..2 + MAX_BUFFERED_MOVES as u32 {
+            assert_eq!(sequencer.receive(sequenced(ply), 1), SequenceOutcome::Buffered);
+        }
+        let overflow = 2 + MAX_BUFFERED_MOVES as u32;
+        assert_eq!(sequencer.receive(sequenced(overflow), 1), SequenceOutcome::Gap);
+        assert!(sequencer.buffered.is_empty());
+    }
+
+    #[test]
+    fn sequencer_reports_a_gap_for_moves_too_far_ahead() {
+        let mut sequencer = MoveSequencer::new("match");
+        let far = 1 + 2 * MAX_BUFFERED_MOVES as u32;
+        assert_eq!(sequencer.receive(sequenced(far), 1), SequenceOutcome::Buffered);
+        assert_eq!(sequencer.receive(sequenced(far + 1), 1), SequenceOutcome::Gap);
+        assert!(sequencer.buffered.is_empty());
+    }
 }
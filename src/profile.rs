@@ -0,0 +1,155 @@
+// Player profile kept on the player's own chain: identity shown to opponents
+// and aggregate statistics updated whenever one of the player's games ends.
+
+use async_graphql::{Enum, SimpleObject};
+use serde::{Deserialize, Serialize};
+
+use crate::{Color, TimeCategory, TimeControl, Variant};
+
+pub const MAX_DISPLAY_NAME_LEN: usize = 32;
+pub const MAX_BIO_LEN: usize = 280;
+pub const MAX_PREFERRED_TIME_CONTROLS: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum GameOutcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+// Games, wins, losses and draws over some subset of a player's games
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, SimpleObject)]
+pub struct ResultCounts {
+    pub games: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+impl ResultCounts {
+    pub fn add(&mut self, outcome: GameOutcome) {
+        self.games += 1;
+        match outcome {
+            GameOutcome::Win => self.wins += 1,
+            GameOutcome::Loss => self.losses += 1,
+            GameOutcome::Draw => self.draws += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct VariantStats {
+    pub variant: Variant,
+    pub results: ResultCounts,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct TimeCategoryStats {
+    pub time_category: TimeCategory,
+    pub results: ResultCounts,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct PlayerStats {
+    pub total: ResultCounts,
+    pub as_white: ResultCounts,
+    pub as_black: ResultCounts,
+    pub by_variant: Vec<VariantStats>,
+    pub by_time_category: Vec<TimeCategoryStats>,
+    pub current_win_streak: u32,
+    pub longest_win_streak: u32,
+}
+
+impl PlayerStats {
+    // Count one finished game (aborted games are not recorded)
+    pub fn record(&mut self, color: Color, outcome: GameOutcome, variant: Variant, time_category: TimeCategory) {
+        self.total.add(outcome);
+        match color {
+            Color::White => self.as_white.add(outcome),
+            Color::Black => self.as_black.add(outcome),
+        }
+
+        match self.by_variant.iter_mut().find(|s| s.variant == variant) {
+            Some(stats) => stats.results.add(outcome),
+            None => {
+                let mut results = ResultCounts::default();
+                results.add(outcome);
+                self.by_variant.push(VariantStats { variant, results });
+            }
+        }
+
+        match self.by_time_category.iter_mut().find(|s| s.time_category == time_category) {
+            Some(stats) => stats.results.add(outcome),
+            None => {
+                let mut results = ResultCounts::default();
+                results.add(outcome);
+                self.by_time_category.push(TimeCategoryStats { time_category, results });
+            }
+        }
+
+        if outcome == GameOutcome::Win {
+            self.current_win_streak += 1;
+            self.longest_win_streak = self.longest_win_streak.max(self.current_win_streak);
+        } else {
+            self.current_win_streak = 0;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct PlayerProfile {
+    pub display_name: String,
+    pub avatar_hash: Option<String>, // Content hash of the avatar image, stored off-chain
+    pub country: Option<String>,     // ISO 3166-1 alpha-2 code
+    pub bio: Option<String>,
+    pub preferred_time_controls: Vec<TimeControl>,
+    pub stats: PlayerStats,
+}
+
+impl PlayerProfile {
+    // Apply the fields of an UpdateProfile; `None` keeps the current value, an empty string clears it
+    pub fn update(
+        &mut self,
+        display_name: Option<String>,
+        avatar_hash: Option<String>,
+        country: Option<String>,
+        bio: Option<String>,
+        preferred_time_controls: Option<Vec<TimeControl>>,
+    ) -> Result<(), String> {
+        let non_empty = |value: String| Some(value).filter(|v| !v.is_empty());
+        if let Some(display_name) = display_name {
+            if display_name.trim().is_empty() || display_name.chars().count() > MAX_DISPLAY_NAME_LEN {
+                return Err(format!("Display name must be 1 to {} characters", MAX_DISPLAY_NAME_LEN));
+            }
+            self.display_name = display_name;
+        }
+        if let Some(avatar_hash) = avatar_hash {
+            if !avatar_hash.chars().all(|c| c.is_ascii_hexdigit()) || avatar_hash.len() > 128 {
+                return Err("Avatar hash must be hexadecimal".to_string());
+            }
+            self.avatar_hash = non_empty(avatar_hash);
+        }
+        if let Some(country) = country {
+            if !country.is_empty() && (country.len() != 2 || !country.chars().all(|c| c.is_ascii_uppercase())) {
+                return Err("Country must be a two-letter ISO code".to_string());
+            }
+            self.country = non_empty(country);
+        }
+        if let Some(bio) = bio {
+            if bio.chars().count() > MAX_BIO_LEN {
+                return Err(format!("Bio must be at most {} characters", MAX_BIO_LEN));
+            }
+            self.bio = non_empty(bio);
+        }
+        if let Some(preferred_time_controls) = preferred_time_controls {
+            if preferred_time_controls.len() > MAX_PREFERRED_TIME_CONTROLS {
+                return Err(format!("At most {} preferred time controls", MAX_PREFERRED_TIME_CONTROLS));
+            }
+            self.preferred_time_controls = preferred_time_controls;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+// Board images built from a FEN, without a browser: SVG for collectibles and clients,
+// and text diagrams for terminals.
+
+use shakmaty::fen::Fen;
+use shakmaty::{CastlingMode, Chess, Position};
+
+use crate::{Color, Square};
+
+pub const SQUARE_SIZE: u32 = 45;
+
+const LIGHT_SQUARE: &str = "#f0d9b5";
+const DARK_SQUARE: &str = "#b58863";
+const LAST_MOVE: &str = "#cdd26a";
+const CHECK: &str = "#e55c5c";
+
+#[derive(Debug, Clone, Copy)]
+pub struct SvgOptions {
+    pub orientation: Color, // Side shown at the bottom
+    pub last_move: Option<(Square, Square)>, // Origin and target of the last move, highlighted
+    pub highlight_check: bool, // Mark the king of the side to move when in check
+    pub coordinates: bool, // Files and ranks along the bottom and left edges
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            orientation: Color::White,
+            last_move: None,
+            highlight_check: true,
+            coordinates: true,
+        }
+    }
+}
+
+// Pieces of the FEN placement field by rank (8 down to 1) and file (a to h)
+pub fn parse_placement(fen: &str) -> Result<[[Option<char>; 8]; 8], String> {
+    let placement = fen.split_whitespace().next().ok_or("Empty FEN")?;
+    let ranks: Vec<&str> = placement.split('/').collect();
+    if ranks.len() != 8 {
+        return Err("FEN must have 8 ranks".to_string());
+    }
+    let mut board = [[None; 8]; 8];
+    for (row, rank) in ranks.iter().enumerate() {
+        let mut file = 0;
+        for c in rank.chars() {
+            if let Some(empty) = c.to_digit(10) {
+                file += empty as usize;
+            } else if "pnbrqkPNBRQK".contains(c) && file < 8 {
+                board[row][file] = Some(c);
+                file += 1;
+            } else {
+                return Err(format!("Invalid FEN character '{}'", c));
+            }
+        }
+        if file != 8 {
+            return Err(format!("FEN rank {} does not have 8 files", 8 - row));
+        }
+    }
+    Ok(board)
+}
+
+// Unicode chess symbol for a FEN piece letter
+pub fn piece_symbol(piece: char) -> char {
+    match piece {
+        'K' => '♔',
+        'Q' => '♕',
+        'R' => '♖',
+        'B' => '♗',
+        'N' => '♘',
+        'P' => '♙',
+        'k' => '♚',
+        'q' => '♛',
+        'r' => '♜',
+        'b' => '♝',
+        'n' => '♞',
+        _ => '♟',
+    }
+}
+
+// Square of the king of the side to move, if that king is in check
+pub fn checked_king(fen: &str) -> Option<Square> {
+    let setup: Fen = fen.parse().ok()?;
+    let position: Chess = setup.into_position(CastlingMode::Standard).ok()?;
+    if !position.is_check() {
+        return None;
+    }
+    let king = position.board().king_of(position.turn())?;
+    Some(Square::new(king.file() as u8, king.rank() as u8))
+}
+
+// Board square drawn at the given row and column (both from the top left) for an orientation
+fn square_at(row: usize, column: usize, orientation: Color) -> Square {
+    match orientation {
+        Color::White => Square::new(column as u8, 7 - row as u8),
+        Color::Black => Square::new(7 - column as u8, row as u8),
+    }
+}
+
+fn piece_on(board: &[[Option<char>; 8]; 8], square: Square) -> Option<char> {
+    board[7 - square.rank as usize][square.file as usize]
+}
+
+pub fn board_svg(fen: &str, options: &SvgOptions) -> Result<String, String> {
+    let board = parse_placement(fen)?;
+    let checked = if options.highlight_check { checked_king(fen) } else { None };
+    let size = SQUARE_SIZE * 8;
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}">"#
+    );
+    for row in 0..8 {
+        for column in 0..8 {
+            let square = square_at(row, column, options.orientation);
+            let x = column as u32 * SQUARE_SIZE;
+            let y = row as u32 * SQUARE_SIZE;
+            let light = (square.file + square.rank) % 2 == 1;
+            let fill = if checked == Some(square) {
+                CHECK
+            } else if options.last_move.is_some_and(|(from, to)| from == square || to == square) {
+                LAST_MOVE
+            } else if light {
+                LIGHT_SQUARE
+            } else {
+                DARK_SQUARE
+            };
+            svg.push_str(&format!(
+                r#"<rect x="{x}" y="{y}" width="{SQUARE_SIZE}" height="{SQUARE_SIZE}" fill="{fill}"/>"#
+            ));
+
+            // Coordinates take the colour of the opposite square so they stay readable
+            let label_fill = if light { DARK_SQUARE } else { LIGHT_SQUARE };
+            if options.coordinates && column == 0 {
+                svg.push_str(&format!(
+                    r#"<text x="{}" y="{}" font-size="10" fill="{label_fill}">{}</text>"#,
+                    x + 2,
+                    y + 11,
+                    square.rank + 1
+                ));
+            }
+            if options.coordinates && row == 7 {
+                svg.push_str(&format!(
+                    r#"<text x="{}" y="{}" font-size="10" fill="{label_fill}" text-anchor="end">{}</text>"#,
+                    x + SQUARE_SIZE - 2,
+                    y + SQUARE_SIZE - 3,
+                    (b'a' + square.file) as char
+                ));
+            }
+
+            if let Some(piece) = piece_on(&board, square) {
+                svg.push_str(&format!(
+                    r#"<text x="{}" y="{}" font-size="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                    x + SQUARE_SIZE / 2,
+                    y + SQUARE_SIZE / 2,
+                    SQUARE_SIZE * 4 / 5,
+                    piece_symbol(piece)
+                ));
+            }
+        }
+    }
+    svg.push_str("</svg>");
+    Ok(svg)
+}
+
+// Text diagram with ranks on the left and files below; `unicode` draws chess symbols instead
+// of FEN letters
+pub fn board_text(fen: &str, orientation: Color, unicode: bool) -> Result<String, String> {
+    let board = parse_placement(fen)?;
+    let mut text = String::new();
+    for row in 0..8 {
+        let rank = square_at(row, 0, orientation).rank;
+        text.push_str(&format!("{} |", rank + 1));
+        for column in 0..8 {
+            let cell = match piece_on(&board, square_at(row, column, orientation)) {
+                Some(piece) if unicode => piece_symbol(piece),
+                Some(piece) => piece,
+                None if unicode => '·',
+                None => '.',
+            };
+            text.push(' ');
+            text.push(cell);
+        }
+        text.push('\n');
+    }
+    text.push_str("  +----------------\n   ");
+    for column in 0..8 {
+        let file = square_at(0, column, orientation).file;
+        text.push(' ');
+        text.push((b'a' + file) as char);
+    }
+    text.push('\n');
+    Ok(text)
+}
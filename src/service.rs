@@ -5,13 +5,23 @@ mod state;
 use std::sync::Arc;
 
 use async_graphql::{EmptySubscription, Object, Request, Response, Schema};
-use linera_sdk::{linera_base_types::WithServiceAbi, views::View, Service, ServiceRuntime};
+use linera_sdk::{
+    linera_base_types::{AccountOwner, Amount, WithServiceAbi},
+    views::View,
+    Service, ServiceRuntime,
+};
+use onchainchess::engine::{self, Analysis, ANALYSIS_NODE_LIMIT, MAX_ANALYSIS_DEPTH};
+use onchainchess::render::{self, SvgOptions};
 use onchainchess::{
-    ChessAbi, ChessMove, Game, MatchStatus, Operation, ChessParameters, Color,
+    ChessAbi, ChessMove, Game, MatchStatus, Operation, ChessParameters, Color, MatchSeries,
+    SeriesFormat, AbandonmentRecord, Bracket, Divergence, Escrow, Collectible, EngineLevel, GameResult, GameUpdate, ChainSettings, PendingJoin, MoveSequencer, Notification, PlayerProfile, PrizePool, Standing, Tournament, TournamentFormat, TimeControl,
 };
 
 use self::state::ChessState;
 
+// Most recent updates kept available to `updatesSince`
+const UPDATE_WINDOW: usize = 256;
+
 linera_sdk::service!(ChessService);
 
 pub struct ChessService {
@@ -38,20 +48,92 @@ impl Service for ChessService {
 
     async fn handle_query(&self, request: Request) -> Response {
         let game = self.state.game.get().clone();
-        let my_ready = self.state.my_ready.get().clone();
-        let opponent_ready = self.state.opponent_ready.get().clone();
-        let last_notification = self.state.last_notification.get().clone();
+        let my_ready = *self.state.my_ready.get();
+        let opponent_ready = *self.state.opponent_ready.get();
+        let notifications = self.state.notifications.get().clone();
+        let divergences = self.state.divergences.get().clone();
+        let move_sequencer = self.state.move_sequencer.get().clone();
+        let profile = self.state.profile.get().clone();
+        let escrow = self.state.escrow.get().clone();
+        let parameters = self.runtime.application_parameters();
+        let abandonments = self
+            .state
+            .abandonments
+            .index_values()
+            .await
+            .expect("Failed to read abandonments")
+            .into_iter()
+            .map(|(chain_id, count)| AbandonmentRecord { chain_id, count })
+            .collect();
+        let tournaments = self
+            .state
+            .tournaments
+            .index_values()
+            .await
+            .expect("Failed to read tournaments")
+            .into_iter()
+            .map(|(_, tournament)| tournament)
+            .collect();
+        let spectated = self
+            .state
+            .spectated
+            .index_values()
+            .await
+            .expect("Failed to read spectated games")
+            .into_iter()
+            .map(|(_, game)| game)
+            .collect();
+        let results = self
+            .state
+            .results
+            .index_values()
+            .await
+            .expect("Failed to read results")
+            .into_iter()
+            .map(|(_, result)| result)
+            .collect();
+        let collectibles = self
+            .state
+            .collectibles
+            .index_values()
+            .await
+            .expect("Failed to read collectibles")
+            .into_iter()
+            .map(|(_, collectible)| collectible)
+            .collect();
+        let update_count = self.state.updates.count();
+        let updates = self
+            .state
+            .updates
+            .read(update_count.saturating_sub(UPDATE_WINDOW)..update_count)
+            .await
+            .expect("Failed to read updates");
         let schema = Schema::build(
             QueryRoot {
                 game,
                 chain_id: self.runtime.chain_id().to_string(),
                 my_ready,
                 opponent_ready,
-                last_notification,
+                notifications,
+                divergences,
+                move_sequencer,
+                profile,
+                settings: self.state.settings.get().clone(),
+                pending_joins: self.state.pending_joins.get().clone(),
+                escrow,
+                parameters,
+                abandonments,
+                tournaments,
+                spectated,
+                results,
+                collectibles,
+                updates,
             },
             MutationRoot {
                 runtime: self.runtime.clone(),
             },
+            // The node service does not run application subscriptions; clients subscribe to the
+            // node's chain notifications and query `updatesSince` on every new block instead
             EmptySubscription,
         )
         .finish();
@@ -64,10 +146,44 @@ struct QueryRoot {
     chain_id: String,
     my_ready: bool,
     opponent_ready: bool,
-    last_notification: Option<String>,
+    notifications: Vec<Notification>,
+    divergences: Vec<Divergence>,
+    move_sequencer: Option<MoveSequencer>,
+    profile: PlayerProfile,
+    escrow: Option<Escrow>,
+    settings: ChainSettings,
+    pending_joins: Vec<PendingJoin>,
+    parameters: ChessParameters,
+    abandonments: Vec<AbandonmentRecord>,
+    tournaments: Vec<Tournament>,
+    spectated: Vec<Game>,
+    results: Vec<GameResult>,
+    collectibles: Vec<Collectible>,
+    updates: Vec<GameUpdate>,
+}
+
+impl QueryRoot {
+    // The current game, or the current or spectated game `match_id`
+    fn find_game(&self, match_id: Option<String>) -> Option<&Game> {
+        match match_id {
+            None => self.game.as_ref(),
+            Some(match_id) => self
+                .game
+                .iter()
+                .chain(self.spectated.iter())
+                .find(|g| g.match_id == match_id),
+        }
+    }
+
+    // FEN and last move of a game position picked by `boardSvg` and `boardText`
+    fn position(&self, match_id: Option<String>, ply: Option<u32>) -> Option<(String, Option<ChessMove>)> {
+        let game = self.find_game(match_id)?;
+        game.position_at(ply.unwrap_or(game.move_history.len() as u32))
+    }
 }
 
 #[Object]
+#[allow(clippy::too_many_arguments)]
 impl QueryRoot {
     async fn game(&self) -> Option<&Game> {
         self.game.as_ref()
@@ -96,6 +212,20 @@ impl QueryRoot {
         self.game.as_ref().map(|g| g.current_turn)
     }
 
+    // Colour played by `player` in the current game
+    async fn player_color(&self, player: AccountOwner) -> Option<Color> {
+        self.game.as_ref().and_then(|g| g.color_of_owner(&player))
+    }
+
+    // Games known to this chain (current and spectated) in which `player` takes part
+    async fn player_games(&self, player: AccountOwner) -> Vec<&Game> {
+        self.game
+            .iter()
+            .chain(self.spectated.iter())
+            .filter(|g| g.player_by_owner(&player).is_some())
+            .collect()
+    }
+
     async fn my_ready(&self) -> bool {
         self.my_ready
     }
@@ -105,7 +235,202 @@ impl QueryRoot {
     }
 
     async fn last_notification(&self) -> Option<String> {
-        self.last_notification.clone()
+        self.notifications.last().map(|n| n.message.clone())
+    }
+
+    // Inbox, newest first
+    async fn notifications(&self, unread_only: Option<bool>) -> Vec<&Notification> {
+        self.notifications
+            .iter()
+            .rev()
+            .filter(|n| !unread_only.unwrap_or(false) || !n.read)
+            .collect()
+    }
+
+    async fn unread_notification_count(&self) -> u32 {
+        self.notifications.iter().filter(|n| !n.read).count() as u32
+    }
+
+    async fn divergences(&self, match_id: Option<String>) -> Vec<&Divergence> {
+        self.divergences
+            .iter()
+            .filter(|d| match_id.as_ref().map(|id| &d.match_id == id).unwrap_or(true))
+            .collect()
+    }
+
+    // Opponent moves received ahead of time, waiting for the missing plies
+    async fn move_sequencer(&self) -> Option<&MoveSequencer> {
+        self.move_sequencer.as_ref()
+    }
+
+    async fn is_in_sync(&self) -> bool {
+        let match_id = self.game.as_ref().map(|g| g.match_id.as_str());
+        !self
+            .divergences
+            .iter()
+            .any(|d| !d.resolved && Some(d.match_id.as_str()) == match_id)
+    }
+
+    // Profile and statistics of this chain's player
+    async fn profile(&self) -> &PlayerProfile {
+        &self.profile
+    }
+
+    // Deployment-wide settings: fees, allowed variants and time controls, hub chain
+    async fn parameters(&self) -> &ChessParameters {
+        &self.parameters
+    }
+
+    // Defaults for matches hosted on this chain
+    async fn settings(&self) -> &ChainSettings {
+        &self.settings
+    }
+
+    // Join requests waiting for the host's decision
+    async fn pending_joins(&self) -> &Vec<PendingJoin> {
+        &self.pending_joins
+    }
+
+    // Both players' stakes of the wagered match hosted on this chain, until the game is settled
+    async fn escrow(&self) -> Option<&Escrow> {
+        self.escrow.as_ref()
+    }
+
+    async fn series(&self) -> Option<&MatchSeries> {
+        self.game.as_ref().and_then(|g| g.series.as_ref())
+    }
+
+    async fn abandonments(&self) -> &Vec<AbandonmentRecord> {
+        &self.abandonments
+    }
+
+    async fn abandonment_count(&self, chain_id: String) -> u32 {
+        self.abandonments
+            .iter()
+            .find(|a| a.chain_id == chain_id)
+            .map(|a| a.count)
+            .unwrap_or(0)
+    }
+
+    async fn tournaments(&self) -> &Vec<Tournament> {
+        &self.tournaments
+    }
+
+    async fn tournament(&self, tournament_id: String) -> Option<&Tournament> {
+        self.tournaments.iter().find(|t| t.tournament_id == tournament_id)
+    }
+
+    async fn tournament_standings(&self, tournament_id: String) -> Vec<Standing> {
+        self.tournaments
+            .iter()
+            .find(|t| t.tournament_id == tournament_id)
+            .map(|t| t.standings())
+            .unwrap_or_default()
+    }
+
+    // Entry fees, sponsor deposits and payouts of a tournament
+    async fn tournament_prize_pool(&self, tournament_id: String) -> Option<&PrizePool> {
+        self.tournaments
+            .iter()
+            .find(|t| t.tournament_id == tournament_id)
+            .map(|t| &t.prize_pool)
+    }
+
+    async fn tournament_bracket(&self, tournament_id: String) -> Option<Bracket> {
+        self.tournaments
+            .iter()
+            .find(|t| t.tournament_id == tournament_id)
+            .map(|t| t.bracket())
+    }
+
+    async fn spectated_games(&self) -> &Vec<Game> {
+        &self.spectated
+    }
+
+    async fn spectated_game(&self, match_id: String) -> Option<&Game> {
+        self.spectated.iter().find(|g| g.match_id == match_id)
+    }
+
+    // Position after `ply` moves (the latest when omitted) of the current game, or of the
+    // spectated game `matchId`, as an SVG image
+    async fn board_svg(
+        &self,
+        match_id: Option<String>,
+        ply: Option<u32>,
+        orientation: Option<Color>,
+        highlight_last_move: Option<bool>,
+        highlight_check: Option<bool>,
+        coordinates: Option<bool>,
+    ) -> Option<String> {
+        let (fen, last_move) = self.position(match_id, ply)?;
+        let options = SvgOptions {
+            orientation: orientation.unwrap_or(Color::White),
+            last_move: last_move
+                .filter(|_| highlight_last_move.unwrap_or(true))
+                .map(|m| (m.from, m.to)),
+            highlight_check: highlight_check.unwrap_or(true),
+            coordinates: coordinates.unwrap_or(true),
+        };
+        render::board_svg(&fen, &options).ok()
+    }
+
+    // The same position as a text diagram, with chess symbols when `unicode` is set
+    async fn board_text(
+        &self,
+        match_id: Option<String>,
+        ply: Option<u32>,
+        orientation: Option<Color>,
+        unicode: Option<bool>,
+    ) -> Option<String> {
+        let (fen, _) = self.position(match_id, ply)?;
+        render::board_text(&fen, orientation.unwrap_or(Color::White), unicode.unwrap_or(false)).ok()
+    }
+
+    // Engine evaluation and principal variation of a position of the current game, or of the
+    // spectated game `matchId`, after `ply` moves (the latest when omitted). Games still being
+    // played cannot be analysed.
+    async fn analysis(
+        &self,
+        match_id: Option<String>,
+        ply: Option<u32>,
+        depth: Option<u32>,
+    ) -> async_graphql::Result<Analysis> {
+        let game = self.find_game(match_id).ok_or("Game not found")?;
+        if game.status == MatchStatus::Active {
+            return Err("Analysis is disabled while the game is being played".into());
+        }
+        let (fen, _) = game
+            .position_at(ply.unwrap_or(game.move_history.len() as u32))
+            .ok_or("No such ply in this game")?;
+        Ok(engine::analyse(&fen, depth.unwrap_or(MAX_ANALYSIS_DEPTH), ANALYSIS_NODE_LIMIT)?)
+    }
+
+    // Results of the games that ended on this chain, played or spectated
+    async fn results(&self) -> &Vec<GameResult> {
+        &self.results
+    }
+
+    async fn result(&self, match_id: String) -> Option<&GameResult> {
+        self.results.iter().find(|r| r.match_id == match_id)
+    }
+
+    // Collectibles held on this chain
+    async fn collectibles(&self) -> &Vec<Collectible> {
+        &self.collectibles
+    }
+
+    async fn collectible(&self, collectible_id: String) -> Option<&Collectible> {
+        self.collectibles.iter().find(|c| c.collectible_id == collectible_id)
+    }
+
+    // Updates with a sequence number of at least `sequence`, oldest first. If the first returned
+    // sequence is larger than requested, the client fell behind and should refetch `game`.
+    async fn updates_since(&self, sequence: u64) -> Vec<&GameUpdate> {
+        self.updates.iter().filter(|u| u.sequence >= sequence).collect()
+    }
+
+    async fn latest_update_sequence(&self) -> Option<u64> {
+        self.updates.last().map(|u| u.sequence)
     }
 
     async fn move_history(&self) -> Vec<onchainchess::MoveRecord> {
@@ -121,27 +446,122 @@ struct MutationRoot {
 }
 
 #[Object]
+#[allow(clippy::too_many_arguments)]
 impl MutationRoot {
-    async fn create_match(&self, host_name: String) -> String {
-        self.runtime
-            .schedule_operation(&Operation::CreateMatch { host_name: host_name.clone() });
+    // Names left out default to the profile's display name
+    async fn create_match(
+        &self,
+        host_name: Option<String>,
+        series: Option<SeriesFormat>,
+        rated: Option<bool>,
+        inactivity_limit_secs: Option<u64>,
+        time_control: Option<TimeControl>,
+        shared_chain: Option<bool>,
+        wager: Option<Amount>,
+    ) -> String {
+        let host_name = host_name.unwrap_or_default();
+        self.runtime.schedule_operation(&Operation::CreateMatch {
+            host_name: host_name.clone(),
+            series,
+            rated: rated.unwrap_or(false),
+            inactivity_limit_secs,
+            time_control,
+            shared_chain: shared_chain.unwrap_or(false),
+            wager,
+        });
         format!("Match created by '{}'", host_name)
     }
 
-    async fn join_match(&self, host_chain_id: String, player_name: String) -> String {
+    async fn play_engine(&self, player_name: Option<String>, level: EngineLevel) -> String {
+        self.runtime.schedule_operation(&Operation::PlayEngine {
+            player_name: player_name.unwrap_or_default(),
+            level,
+        });
+        format!("Game against the {:?} engine scheduled", level)
+    }
+
+    async fn join_match(
+        &self,
+        host_chain_id: String,
+        player_name: Option<String>,
+        wager: Option<Amount>,
+    ) -> String {
         self.runtime.schedule_operation(&Operation::JoinMatch {
             host_chain_id: host_chain_id.clone(),
-            player_name: player_name.clone(),
+            player_name: player_name.unwrap_or_default(),
+            wager,
         });
         format!("Join request sent to {}", host_chain_id)
     }
 
+    async fn update_profile(
+        &self,
+        display_name: Option<String>,
+        avatar_hash: Option<String>,
+        country: Option<String>,
+        bio: Option<String>,
+        preferred_time_controls: Option<Vec<TimeControl>>,
+    ) -> String {
+        self.runtime.schedule_operation(&Operation::UpdateProfile {
+            display_name,
+            avatar_hash,
+            country,
+            bio,
+            preferred_time_controls,
+        });
+        "Profile update scheduled".to_string()
+    }
+
+    async fn update_settings(&self, settings: ChainSettings) -> String {
+        self.runtime.schedule_operation(&Operation::UpdateSettings { settings });
+        "Settings update scheduled".to_string()
+    }
+
+    async fn accept_join(&self, player_chain_id: String) -> String {
+        self.runtime.schedule_operation(&Operation::AcceptJoin { player_chain_id });
+        "Join acceptance scheduled".to_string()
+    }
+
+    async fn decline_join(&self, player_chain_id: String) -> String {
+        self.runtime.schedule_operation(&Operation::DeclineJoin { player_chain_id });
+        "Join decline scheduled".to_string()
+    }
+
+    async fn mint_collectible(&self) -> String {
+        self.runtime.schedule_operation(&Operation::MintCollectible);
+        "Collectible mint scheduled".to_string()
+    }
+
+    async fn transfer_collectible(
+        &self,
+        collectible_id: String,
+        recipient_chain_id: String,
+        recipient_owner: Option<AccountOwner>,
+    ) -> String {
+        self.runtime.schedule_operation(&Operation::TransferCollectible {
+            collectible_id,
+            recipient_chain_id,
+            recipient_owner,
+        });
+        "Collectible transfer scheduled".to_string()
+    }
+
     async fn make_move(&self, chess_move: ChessMove) -> String {
         self.runtime
             .schedule_operation(&Operation::MakeMove { chess_move });
         "Move scheduled".to_string()
     }
 
+    async fn claim_timeout(&self) -> String {
+        self.runtime.schedule_operation(&Operation::ClaimTimeout);
+        "Timeout claim scheduled".to_string()
+    }
+
+    async fn berserk(&self) -> String {
+        self.runtime.schedule_operation(&Operation::Berserk);
+        "Berserk scheduled".to_string()
+    }
+
     async fn resign_match(&self) -> String {
         self.runtime.schedule_operation(&Operation::ResignMatch);
         "Resignation scheduled".to_string()
@@ -151,4 +571,153 @@ impl MutationRoot {
         self.runtime.schedule_operation(&Operation::EndGame { status });
         "Game end scheduled".to_string()
     }
+
+    async fn cancel_match(&self) -> String {
+        self.runtime.schedule_operation(&Operation::CancelMatch);
+        "Match cancellation scheduled".to_string()
+    }
+
+    async fn abort_game(&self) -> String {
+        self.runtime.schedule_operation(&Operation::AbortGame);
+        "Game abort scheduled".to_string()
+    }
+
+    async fn request_takeback(&self) -> String {
+        self.runtime.schedule_operation(&Operation::RequestTakeback);
+        "Takeback request scheduled".to_string()
+    }
+
+    async fn accept_takeback(&self) -> String {
+        self.runtime.schedule_operation(&Operation::AcceptTakeback);
+        "Takeback acceptance scheduled".to_string()
+    }
+
+    async fn claim_abandonment(&self) -> String {
+        self.runtime.schedule_operation(&Operation::ClaimAbandonment);
+        "Abandonment claim scheduled".to_string()
+    }
+
+    async fn mark_notifications_read(&self, ids: Option<Vec<u64>>) -> String {
+        self.runtime
+            .schedule_operation(&Operation::MarkNotificationsRead { ids });
+        "Notifications marked as read".to_string()
+    }
+
+    async fn request_resync(&self) -> String {
+        self.runtime.schedule_operation(&Operation::RequestResync);
+        "Resync requested".to_string()
+    }
+
+    async fn set_ready(&self) -> String {
+        self.runtime.schedule_operation(&Operation::SetReady);
+        "Ready confirmation scheduled".to_string()
+    }
+
+    async fn offer_draw(&self) -> String {
+        self.runtime.schedule_operation(&Operation::OfferDraw);
+        "Draw offer scheduled".to_string()
+    }
+
+    async fn accept_draw(&self) -> String {
+        self.runtime.schedule_operation(&Operation::AcceptDraw);
+        "Draw acceptance scheduled".to_string()
+    }
+
+    async fn watch_game(&self, host_chain_id: String, match_id: String) -> String {
+        self.runtime.schedule_operation(&Operation::WatchGame {
+            host_chain_id,
+            match_id: match_id.clone(),
+        });
+        format!("Watching game {}", match_id)
+    }
+
+    async fn unwatch_game(&self, host_chain_id: String, match_id: String) -> String {
+        self.runtime.schedule_operation(&Operation::UnwatchGame {
+            host_chain_id,
+            match_id: match_id.clone(),
+        });
+        format!("Stopped watching game {}", match_id)
+    }
+
+    async fn create_tournament(
+        &self,
+        name: String,
+        format: TournamentFormat,
+        rounds: Option<u32>,
+        time_control: Option<TimeControl>,
+        duration_minutes: Option<u32>,
+        mini_match: Option<SeriesFormat>,
+        entry_fee: Option<Amount>,
+        payout_percentages: Option<Vec<u32>>,
+    ) -> String {
+        self.runtime.schedule_operation(&Operation::CreateTournament {
+            name: name.clone(),
+            format,
+            rounds,
+            time_control,
+            duration_minutes,
+            mini_match,
+            entry_fee,
+            payout_percentages,
+        });
+        format!("Tournament '{}' created", name)
+    }
+
+    async fn register_for_tournament(
+        &self,
+        organizer_chain_id: String,
+        tournament_id: String,
+        player_name: Option<String>,
+        entry_fee: Option<Amount>,
+    ) -> String {
+        self.runtime.schedule_operation(&Operation::RegisterForTournament {
+            organizer_chain_id: organizer_chain_id.clone(),
+            tournament_id,
+            player_name: player_name.unwrap_or_default(),
+            entry_fee,
+        });
+        format!("Registration sent to {}", organizer_chain_id)
+    }
+
+    async fn sponsor_tournament(
+        &self,
+        organizer_chain_id: String,
+        tournament_id: String,
+        amount: Amount,
+    ) -> String {
+        self.runtime.schedule_operation(&Operation::SponsorTournament {
+            organizer_chain_id: organizer_chain_id.clone(),
+            tournament_id,
+            amount,
+        });
+        format!("Sponsorship sent to {}", organizer_chain_id)
+    }
+
+    async fn start_tournament(&self, tournament_id: String) -> String {
+        self.runtime
+            .schedule_operation(&Operation::StartTournament { tournament_id });
+        "Tournament start scheduled".to_string()
+    }
+
+    async fn close_tournament(&self, tournament_id: String) -> String {
+        self.runtime
+            .schedule_operation(&Operation::CloseTournament { tournament_id });
+        "Tournament close scheduled".to_string()
+    }
+
+    async fn cancel_tournament(&self, tournament_id: String) -> String {
+        self.runtime
+            .schedule_operation(&Operation::CancelTournament { tournament_id });
+        "Tournament cancellation scheduled".to_string()
+    }
+
+    async fn offer_rematch(&self) -> String {
+        self.runtime.schedule_operation(&Operation::OfferRematch);
+        "Rematch offer scheduled".to_string()
+    }
+
+    async fn accept_rematch(&self) -> String {
+        self.runtime.schedule_operation(&Operation::AcceptRematch);
+        "Rematch acceptance scheduled".to_string()
+    }
 }
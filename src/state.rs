@@ -1,5 +1,8 @@
-use linera_sdk::views::{RegisterView, RootView, ViewStorageContext};
-use onchainchess::Game;
+use linera_sdk::views::{LogView, MapView, RegisterView, RootView, ViewStorageContext};
+use onchainchess::{
+    ChainSettings, Collectible, Divergence, Escrow, Game, GameResult, GameUpdate, MoveSequencer, Notification,
+    PendingJoin, PlayerProfile, ResultWatcher, Tournament,
+};
 
 #[derive(RootView)]
 #[view(context = ViewStorageContext)]
@@ -7,5 +10,36 @@ pub struct ChessState {
     pub game: RegisterView<Option<Game>>,
     pub my_ready: RegisterView<bool>,
     pub opponent_ready: RegisterView<bool>,
-    pub last_notification: RegisterView<Option<String>>,
+    // Notification inbox, oldest first
+    pub notifications: RegisterView<Vec<Notification>>,
+    pub next_notification_id: RegisterView<u64>,
+    pub finalized_match_id: RegisterView<Option<String>>,
+    // Mismatches detected between our copy of the game and the opponent's, newest last
+    pub divergences: RegisterView<Vec<Divergence>>,
+    // Orders the opponent's moves of the current game, buffering early arrivals
+    pub move_sequencer: RegisterView<Option<MoveSequencer>>,
+    // Stakes of the wagered match hosted here, held until the game is settled
+    pub escrow: RegisterView<Option<Escrow>>,
+    // Profile and statistics of this chain's player
+    pub profile: RegisterView<PlayerProfile>,
+    // Defaults for the matches hosted on this chain
+    pub settings: RegisterView<ChainSettings>,
+    // Join requests waiting for the host when auto-accept is off, oldest first
+    pub pending_joins: RegisterView<Vec<PendingJoin>>,
+    // Abandoned games per player chain, for fair-play review
+    pub abandonments: MapView<String, u32>,
+    // Tournaments organized from this chain
+    pub tournaments: MapView<String, Tournament>,
+    // Read-only mirrors of games watched on other chains, by match ID
+    pub spectated: MapView<String, Game>,
+    // Results of the games that ended on this chain (played or spectated), by match ID
+    pub results: MapView<String, GameResult>,
+    // Applications to call when a match ends
+    pub result_watchers: RegisterView<Vec<ResultWatcher>>,
+    // Collectibles held on this chain, by collectible ID
+    pub collectibles: MapView<String, Collectible>,
+    // IDs of the collectibles minted from this chain's games, even if transferred since
+    pub minted_collectibles: RegisterView<Vec<String>>,
+    // Append-only log of game updates, polled by clients after each new block
+    pub updates: LogView<GameUpdate>,
 }
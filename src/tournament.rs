@@ -0,0 +1,1579 @@
+// Tournament bookkeeping shared by the contract and the service:
+// pairing generation per format and standings with tiebreaks.
+
+use async_graphql::{Enum, SimpleObject};
+use linera_sdk::linera_base_types::{AccountOwner, Amount};
+use serde::{Deserialize, Serialize};
+
+use crate::{MatchSeries, SeriesFormat, SeriesGameResult, SeriesKind, SeriesPhase, TimeControl};
+
+// Knockout pairings are mini-matches: two games, then two rapid tiebreaks, then Armageddon
+pub const DEFAULT_MINI_MATCH: SeriesFormat = SeriesFormat {
+    kind: SeriesKind::BestOf,
+    target: 2,
+    armageddon_tiebreak: true,
+    tiebreak_games: Some(2),
+};
+
+pub const TIEBREAK_TIME_CONTROL: TimeControl = TimeControl {
+    base_secs: 600,
+    increment_secs: 5,
+};
+
+pub const ARMAGEDDON_TIME_CONTROL: TimeControl = TimeControl {
+    base_secs: 300,
+    increment_secs: 0,
+};
+
+// Share of the prize pool per final place, in percent
+pub const DEFAULT_PAYOUT_PERCENTAGES: [u32; 3] = [50, 30, 20];
+pub const MAX_PAID_PLACES: usize = 10;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Enum)]
+pub enum TournamentFormat {
+    RoundRobin,
+    Swiss,
+    SingleElimination,
+    DoubleElimination,
+    Arena, // Fixed duration, players are re-paired as soon as they finish
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Enum)]
+pub enum BracketSide {
+    Winners,
+    Losers,
+    GrandFinal,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Enum)]
+pub enum TournamentStatus {
+    Registration,
+    InProgress,
+    Finished,
+    Cancelled, // Called off by the organizer; every deposit is refunded
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Enum)]
+pub enum PairingResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Enum)]
+pub enum DepositKind {
+    EntryFee,
+    Sponsorship,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct PoolDeposit {
+    pub kind: DepositKind,
+    pub chain_id: String,
+    pub owner: AccountOwner,
+    pub amount: Amount,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct PrizePayout {
+    pub rank: u32,
+    pub chain_id: String,
+    pub owner: Option<AccountOwner>, // Paid to the chain's own account when unknown
+    pub amount: Amount,
+}
+
+// Entry fees and sponsor deposits, held by the application on the organizer's chain
+// until the tournament finishes or is cancelled. Every deposit and payout is kept for auditing.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct PrizePool {
+    pub entry_fee: Option<Amount>,
+    pub payout_percentages: Vec<u32>,
+    pub entry_fees: Amount,
+    pub sponsored: Amount,
+    pub deposits: Vec<PoolDeposit>,
+    pub platform_fee: Amount, // Taken from the entry fees at distribution
+    pub payouts: Vec<PrizePayout>,
+    pub distributed: bool,
+}
+
+impl Default for PrizePool {
+    fn default() -> Self {
+        Self {
+            entry_fee: None,
+            payout_percentages: DEFAULT_PAYOUT_PERCENTAGES.to_vec(),
+            entry_fees: Amount::ZERO,
+            sponsored: Amount::ZERO,
+            deposits: Vec::new(),
+            platform_fee: Amount::ZERO,
+            payouts: Vec::new(),
+            distributed: false,
+        }
+    }
+}
+
+impl PrizePool {
+    pub fn new(entry_fee: Option<Amount>, payout_percentages: Option<Vec<u32>>) -> Result<Self, String> {
+        let payout_percentages = payout_percentages.unwrap_or_else(|| DEFAULT_PAYOUT_PERCENTAGES.to_vec());
+        if payout_percentages.is_empty() || payout_percentages.len() > MAX_PAID_PLACES {
+            return Err(format!("Between 1 and {} paid places", MAX_PAID_PLACES));
+        }
+        if payout_percentages.contains(&0) || payout_percentages.iter().sum::<u32>() != 100 {
+            return Err("Payout percentages must be positive and add up to 100".to_string());
+        }
+        Ok(Self {
+            entry_fee: entry_fee.filter(|fee| *fee > Amount::ZERO),
+            payout_percentages,
+            ..Self::default()
+        })
+    }
+
+    pub fn total(&self) -> Amount {
+        self.entry_fees.saturating_add(self.sponsored)
+    }
+
+    pub fn add_deposit(&mut self, deposit: PoolDeposit) {
+        match deposit.kind {
+            DepositKind::EntryFee => self.entry_fees = self.entry_fees.saturating_add(deposit.amount),
+            DepositKind::Sponsorship => self.sponsored = self.sponsored.saturating_add(deposit.amount),
+        }
+        self.deposits.push(deposit);
+    }
+
+    // Split the pool, less the platform fee, over the final ranking. Places beyond the size
+    // of the field and rounding leftovers go to the winner, so the whole pool is paid out.
+    pub fn compute_payouts(&self, ranking: &[&TournamentPlayer]) -> Vec<PrizePayout> {
+        let total = u128::from(self.total().saturating_sub(self.platform_fee));
+        let mut payouts: Vec<PrizePayout> = self
+            .payout_percentages
+            .iter()
+            .zip(ranking)
+            .enumerate()
+            .map(|(index, (percentage, player))| PrizePayout {
+                rank: index as u32 + 1,
+                chain_id: player.chain_id.clone(),
+                owner: player.owner,
+                amount: Amount::from_attos(total * *percentage as u128 / 100),
+            })
+            .collect();
+        let paid: u128 = payouts.iter().map(|p| u128::from(p.amount)).sum();
+        if let Some(winner) = payouts.first_mut() {
+            winner.amount = Amount::from_attos(u128::from(winner.amount) + total - paid);
+        }
+        payouts
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct TournamentPlayer {
+    pub chain_id: String,
+    pub name: String,
+    pub seed: u32,
+    pub owner: Option<AccountOwner>,
+}
+
+// One board of a round; a pairing without a black player is a bye.
+// In knockouts `series` holds the mini-match and `result` is set once it is decided.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct Pairing {
+    pub board: u32,
+    pub white_chain_id: String,
+    pub black_chain_id: Option<String>,
+    pub match_id: Option<String>,
+    pub result: Option<PairingResult>,
+    pub bracket: Option<BracketSide>,
+    pub series: Option<MatchSeries>,
+}
+
+// Outcome of feeding one game result into the tournament
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameRecord {
+    Ignored,      // Unknown board, duplicate or stale report
+    NextGame,     // Mini-match continues with another game on the same board
+    PairingDone,  // The board is decided
+}
+
+// A game the organizer should start next
+#[derive(Debug, Clone)]
+pub struct ScheduledGame {
+    pub white_chain_id: String,
+    pub black_chain_id: String,
+    pub game_number: u32,
+    pub time_control: Option<TimeControl>,
+    pub armageddon: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct BracketMatch {
+    pub board: u32,
+    pub white_chain_id: String,
+    pub black_chain_id: Option<String>,
+    pub white_score: f64,
+    pub black_score: f64,
+    pub winner_chain_id: Option<String>,
+    pub match_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct BracketRound {
+    pub round: u32,
+    pub side: BracketSide,
+    pub matches: Vec<BracketMatch>,
+}
+
+// Knockout bracket, grouped by round and side, ready for rendering
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct Bracket {
+    pub tournament_id: String,
+    pub format: TournamentFormat,
+    pub rounds: Vec<BracketRound>,
+    pub champion_chain_id: Option<String>,
+}
+
+impl Pairing {
+    pub fn is_bye(&self) -> bool {
+        self.black_chain_id.is_none()
+    }
+
+    // Half points scored by `chain_id` on this board (a bye counts as a win)
+    pub fn half_points_for(&self, chain_id: &str) -> Option<u32> {
+        if self.is_bye() {
+            return (self.white_chain_id == chain_id).then_some(2);
+        }
+        let is_white = self.white_chain_id == chain_id;
+        let is_black = self.black_chain_id.as_deref() == Some(chain_id);
+        if !is_white && !is_black {
+            return None;
+        }
+        Some(match self.result? {
+            PairingResult::Draw => 1,
+            PairingResult::WhiteWins if is_white => 2,
+            PairingResult::BlackWins if is_black => 2,
+            _ => 0,
+        })
+    }
+
+    pub fn opponent_of(&self, chain_id: &str) -> Option<&str> {
+        if self.white_chain_id == chain_id {
+            self.black_chain_id.as_deref()
+        } else if self.black_chain_id.as_deref() == Some(chain_id) {
+            Some(&self.white_chain_id)
+        } else {
+            None
+        }
+    }
+
+    // Winner of the board; draws go to White, who is always the higher seed in knockouts
+    pub fn advancing_chain_id(&self) -> Option<&str> {
+        match (self.result, &self.black_chain_id) {
+            (_, None) => Some(&self.white_chain_id),
+            (Some(PairingResult::BlackWins), Some(black)) => Some(black),
+            (Some(_), Some(_)) => Some(&self.white_chain_id),
+            (None, Some(_)) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct TournamentRound {
+    pub number: u32,
+    pub pairings: Vec<Pairing>,
+}
+
+impl TournamentRound {
+    pub fn is_complete(&self) -> bool {
+        self.pairings.iter().all(|p| p.is_bye() || p.result.is_some())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct Standing {
+    pub rank: u32,
+    pub chain_id: String,
+    pub name: String,
+    pub points: f64,
+    pub buchholz: f64,
+    pub sonneborn_berger: f64,
+}
+
+// Arena points: win 2, draw 1; wins and draws count double on a streak of two or more
+// wins, and a berserk win earns one extra point
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct ArenaScore {
+    pub chain_id: String,
+    pub points: u32,
+    pub win_streak: u32,
+    pub games: u32,
+    pub berserk_wins: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct Arena {
+    pub duration_secs: u64,
+    pub ends_at: Option<u64>,
+    pub waiting: Vec<String>,
+    pub scores: Vec<ArenaScore>,
+}
+
+impl Arena {
+    fn score_mut(&mut self, chain_id: &str) -> &mut ArenaScore {
+        if let Some(index) = self.scores.iter().position(|s| s.chain_id == chain_id) {
+            return &mut self.scores[index];
+        }
+        self.scores.push(ArenaScore {
+            chain_id: chain_id.to_string(),
+            points: 0,
+            win_streak: 0,
+            games: 0,
+            berserk_wins: 0,
+        });
+        self.scores.last_mut().unwrap()
+    }
+
+    pub fn points(&self, chain_id: &str) -> u32 {
+        self.scores
+            .iter()
+            .find(|s| s.chain_id == chain_id)
+            .map(|s| s.points)
+            .unwrap_or(0)
+    }
+
+    fn score_game(&mut self, chain_id: &str, half_points: u32, berserk: bool) {
+        let score = self.score_mut(chain_id);
+        let on_fire = score.win_streak >= 2;
+        let multiplier = if on_fire { 2 } else { 1 };
+        score.games += 1;
+        match half_points {
+            2 => {
+                score.points += 2 * multiplier;
+                score.win_streak += 1;
+                if berserk {
+                    score.points += 1;
+                    score.berserk_wins += 1;
+                }
+            }
+            1 => {
+                score.points += multiplier;
+                score.win_streak = 0;
+            }
+            _ => score.win_streak = 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(rename_fields = "camelCase")]
+pub struct Tournament {
+    pub tournament_id: String,
+    pub name: String,
+    pub format: TournamentFormat,
+    pub organizer_chain_id: String,
+    pub status: TournamentStatus,
+    pub total_rounds: u32,
+    pub players: Vec<TournamentPlayer>,
+    pub rounds: Vec<TournamentRound>,
+    pub time_control: Option<TimeControl>,
+    pub arena: Option<Arena>,
+    pub mini_match: Option<SeriesFormat>,
+    pub prize_pool: PrizePool,
+}
+
+impl Tournament {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        tournament_id: String,
+        name: String,
+        format: TournamentFormat,
+        organizer_chain_id: String,
+        total_rounds: u32,
+        time_control: Option<TimeControl>,
+        duration_secs: u64,
+        mini_match: Option<SeriesFormat>,
+    ) -> Self {
+        let arena = (format == TournamentFormat::Arena).then(|| Arena {
+            duration_secs,
+            ends_at: None,
+            waiting: Vec::new(),
+            scores: Vec::new(),
+        });
+        // A knockout board must produce a winner, so its mini-matches always end in Armageddon
+        let mini_match = match (mini_match, Self::is_knockout_format(format)) {
+            (Some(series), true) => Some(SeriesFormat { armageddon_tiebreak: true, ..series }),
+            (None, true) => Some(DEFAULT_MINI_MATCH),
+            (series, false) => series,
+        };
+        Self {
+            tournament_id,
+            name,
+            format,
+            organizer_chain_id,
+            status: TournamentStatus::Registration,
+            total_rounds,
+            players: Vec::new(),
+            rounds: Vec::new(),
+            time_control,
+            arena,
+            mini_match,
+            prize_pool: PrizePool::default(),
+        }
+    }
+
+    fn is_knockout_format(format: TournamentFormat) -> bool {
+        matches!(
+            format,
+            TournamentFormat::SingleElimination | TournamentFormat::DoubleElimination
+        )
+    }
+
+    // Arenas accept late joiners, who go straight into the pairing pool
+    pub fn register(
+        &mut self,
+        chain_id: String,
+        name: String,
+        owner: Option<AccountOwner>,
+    ) -> Result<(), String> {
+        let late_arena_entry = self.status == TournamentStatus::InProgress && self.arena.is_some();
+        if self.status != TournamentStatus::Registration && !late_arena_entry {
+            return Err("Registration is closed".to_string());
+        }
+        if self.players.iter().any(|p| p.chain_id == chain_id) {
+            return Err("Already registered".to_string());
+        }
+        let seed = self.players.len() as u32 + 1;
+        if let (true, Some(arena)) = (late_arena_entry, self.arena.as_mut()) {
+            arena.waiting.push(chain_id.clone());
+        }
+        self.players.push(TournamentPlayer { chain_id, name, seed, owner });
+        Ok(())
+    }
+
+    pub fn player(&self, chain_id: &str) -> Option<&TournamentPlayer> {
+        self.players.iter().find(|p| p.chain_id == chain_id)
+    }
+
+    pub fn pairing(&self, round: u32, board: u32) -> Option<&Pairing> {
+        self.rounds
+            .iter()
+            .find(|r| r.number == round)
+            .and_then(|r| r.pairings.iter().find(|p| p.board == board))
+    }
+
+    pub fn current_round(&self) -> Option<&TournamentRound> {
+        self.rounds.last()
+    }
+
+    // Number of rounds the format needs; `requested` only applies to Swiss
+    pub fn rounds_for(format: TournamentFormat, players: usize, requested: Option<u32>) -> u32 {
+        let log2 = (players.max(2) as f64).log2().ceil() as u32;
+        match format {
+            TournamentFormat::RoundRobin => {
+                if players % 2 == 1 {
+                    players as u32
+                } else {
+                    players.saturating_sub(1) as u32
+                }
+            }
+            TournamentFormat::Swiss => requested.unwrap_or(log2),
+            TournamentFormat::SingleElimination => log2,
+            // Upper bound: the winners' bracket, the losers' bracket and a possible reset final
+            TournamentFormat::DoubleElimination => 2 * log2 + 1,
+            TournamentFormat::Arena => 0,
+        }
+    }
+
+    // Records a game result; returns false if the board is unknown or already scored.
+    // In an arena both players also score arena points and return to the pairing pool.
+    pub fn record_result(
+        &mut self,
+        round: u32,
+        board: u32,
+        result: PairingResult,
+        white_berserk: bool,
+        black_berserk: bool,
+    ) -> bool {
+        let pairing = self
+            .rounds
+            .iter_mut()
+            .find(|r| r.number == round)
+            .and_then(|r| r.pairings.iter_mut().find(|p| p.board == board));
+        let pairing = match pairing {
+            Some(pairing) if pairing.result.is_none() && !pairing.is_bye() => pairing,
+            _ => return false,
+        };
+        pairing.result = Some(result);
+
+        if let Some(arena) = self.arena.as_mut() {
+            let white = pairing.white_chain_id.clone();
+            let black = pairing.black_chain_id.clone().unwrap_or_default();
+            let white_points = pairing.half_points_for(&white).unwrap_or(0);
+            let black_points = pairing.half_points_for(&black).unwrap_or(0);
+            arena.score_game(&white, white_points, white_berserk);
+            arena.score_game(&black, black_points, black_berserk);
+            arena.waiting.push(white);
+            arena.waiting.push(black);
+        }
+        true
+    }
+
+    // Feeds a reported game into its board: plain pairings are decided at once, mini-matches
+    // record the game and either continue or decide the board
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_game(
+        &mut self,
+        round: u32,
+        board: u32,
+        game_number: u32,
+        match_id: String,
+        winner_chain_id: Option<String>,
+        white_berserk: bool,
+        black_berserk: bool,
+    ) -> GameRecord {
+        if self.status != TournamentStatus::InProgress {
+            return GameRecord::Ignored;
+        }
+        let pairing = match self
+            .rounds
+            .iter_mut()
+            .find(|r| r.number == round)
+            .and_then(|r| r.pairings.iter_mut().find(|p| p.board == board))
+        {
+            Some(pairing) if pairing.result.is_none() && !pairing.is_bye() => pairing,
+            _ => return GameRecord::Ignored,
+        };
+
+        let series = match pairing.series.as_mut() {
+            Some(series) => series,
+            None => {
+                pairing.match_id = Some(match_id);
+                let result = match winner_chain_id {
+                    Some(winner) if winner == pairing.white_chain_id => PairingResult::WhiteWins,
+                    Some(_) => PairingResult::BlackWins,
+                    None => PairingResult::Draw,
+                };
+                return if self.record_result(round, board, result, white_berserk, black_berserk) {
+                    GameRecord::PairingDone
+                } else {
+                    GameRecord::Ignored
+                };
+            }
+        };
+
+        // Mini-match games arrive one at a time, in order
+        if game_number as usize != series.results.len() {
+            return GameRecord::Ignored;
+        }
+        let (game_white, game_black) = Self::mini_match_colors(&pairing.white_chain_id, pairing.black_chain_id.as_deref().unwrap_or_default(), game_number);
+        let armageddon = series.needs_armageddon();
+        series.record(SeriesGameResult {
+            match_id: match_id.clone(),
+            white_chain_id: game_white,
+            black_chain_id: game_black,
+            winner_chain_id,
+            armageddon,
+        });
+        pairing.match_id = Some(match_id);
+        if !series.finished {
+            return GameRecord::NextGame;
+        }
+        pairing.result = Some(match series.winner_chain_id.as_deref() {
+            Some(winner) if winner == pairing.white_chain_id => PairingResult::WhiteWins,
+            Some(_) => PairingResult::BlackWins,
+            None => PairingResult::Draw, // Drawn series without an Armageddon decider
+        });
+        GameRecord::PairingDone
+    }
+
+    // Colours alternate within a mini-match, starting with the pairing's White
+    fn mini_match_colors(white: &str, black: &str, game_number: u32) -> (String, String) {
+        if game_number % 2 == 1 {
+            (black.to_string(), white.to_string())
+        } else {
+            (white.to_string(), black.to_string())
+        }
+    }
+
+    // Next game to start on a board, if it still needs one
+    pub fn next_game(&self, round: u32, board: u32) -> Option<ScheduledGame> {
+        let pairing = self.pairing(round, board)?;
+        let black = pairing.black_chain_id.as_ref()?;
+        if pairing.result.is_some() {
+            return None;
+        }
+
+        let series = match pairing.series.as_ref() {
+            Some(series) => series,
+            None => {
+                return Some(ScheduledGame {
+                    white_chain_id: pairing.white_chain_id.clone(),
+                    black_chain_id: black.clone(),
+                    game_number: 0,
+                    time_control: self.time_control,
+                    armageddon: false,
+                })
+            }
+        };
+        let game_number = series.results.len() as u32;
+        let (white_chain_id, black_chain_id) =
+            Self::mini_match_colors(&pairing.white_chain_id, black, game_number);
+        let (time_control, armageddon) = match series.next_phase()? {
+            SeriesPhase::Regular => (self.time_control, false),
+            SeriesPhase::Tiebreak => (Some(TIEBREAK_TIME_CONTROL), false),
+            SeriesPhase::Armageddon => (Some(ARMAGEDDON_TIME_CONTROL), true),
+        };
+        Some(ScheduledGame {
+            white_chain_id,
+            black_chain_id,
+            game_number,
+            time_control,
+            armageddon,
+        })
+    }
+
+    // Starts the first round or moves on once the current round is complete.
+    // Returns the new round, or None when the tournament is over.
+    // Arenas instead pair whoever is waiting until `ends_at`, and finish once the
+    // last game after that is in; None then just means nobody could be paired.
+    pub fn advance(&mut self, now: u64) -> Result<Option<&TournamentRound>, String> {
+        if self.status == TournamentStatus::Finished {
+            return Err("Tournament already finished".to_string());
+        }
+        if self.status == TournamentStatus::Cancelled {
+            return Err("Tournament was cancelled".to_string());
+        }
+        if self.arena.is_some() {
+            return self.advance_arena(now);
+        }
+        if let Some(round) = self.current_round() {
+            if !round.is_complete() {
+                return Err("Current round is not complete".to_string());
+            }
+        } else {
+            if self.players.len() < 2 {
+                return Err("Not enough players".to_string());
+            }
+            // A Swiss length of zero means "pick one from the field size"
+            self.total_rounds = match self.format {
+                TournamentFormat::Swiss if self.total_rounds > 0 => self.total_rounds,
+                format => Self::rounds_for(format, self.players.len(), None),
+            };
+        }
+
+        let number = self.rounds.len() as u32 + 1;
+        let finished = match self.format {
+            TournamentFormat::SingleElimination => self.knockout_survivors().len() < 2,
+            TournamentFormat::DoubleElimination => self.alive_players().len() < 2,
+            _ => number > self.total_rounds,
+        };
+        if finished {
+            self.status = TournamentStatus::Finished;
+            return Ok(None);
+        }
+
+        let pairs: Vec<(String, Option<String>, Option<BracketSide>)> = match self.format {
+            TournamentFormat::RoundRobin => self.round_robin_pairs(number).into_iter().map(|(w, b)| (w, b, None)).collect(),
+            TournamentFormat::Swiss => self.swiss_pairs().into_iter().map(|(w, b)| (w, b, None)).collect(),
+            TournamentFormat::SingleElimination => self
+                .knockout_pairs()
+                .into_iter()
+                .map(|(w, b)| (w, b, Some(BracketSide::Winners)))
+                .collect(),
+            TournamentFormat::DoubleElimination => self.double_elimination_pairs(),
+            TournamentFormat::Arena => unreachable!("Arenas are paired by advance_arena"),
+        };
+        let pairings = pairs
+            .into_iter()
+            .enumerate()
+            .map(|(i, (white, black, bracket))| {
+                let series = match (self.mini_match, black.as_deref()) {
+                    (Some(format), Some(black)) => Some(MatchSeries::new(
+                        format!("{}-{}-{}", self.tournament_id, number, i + 1),
+                        format,
+                        &white,
+                        black,
+                    )),
+                    _ => None,
+                };
+                Pairing {
+                    board: i as u32 + 1,
+                    white_chain_id: white,
+                    black_chain_id: black,
+                    match_id: None,
+                    result: None,
+                    bracket,
+                    series,
+                }
+            })
+            .collect();
+        self.status = TournamentStatus::InProgress;
+        self.rounds.push(TournamentRound { number, pairings });
+        Ok(self.rounds.last())
+    }
+
+    // Calls the tournament off at any stage before it finishes; games still being played
+    // are no longer scored
+    pub fn cancel(&mut self) -> Result<(), String> {
+        match self.status {
+            TournamentStatus::Finished => Err("Tournament already finished".to_string()),
+            TournamentStatus::Cancelled => Err("Tournament already cancelled".to_string()),
+            _ => {
+                if let Some(arena) = self.arena.as_mut() {
+                    arena.waiting.clear();
+                }
+                self.status = TournamentStatus::Cancelled;
+                Ok(())
+            }
+        }
+    }
+
+    // Ends an arena once its time is up, without waiting for a late result to do so;
+    // games still being played are left unscored
+    pub fn close(&mut self, now: u64) -> Result<(), String> {
+        if self.status != TournamentStatus::InProgress {
+            return Err("Tournament is not in progress".to_string());
+        }
+        let arena = self.arena.as_mut().ok_or("Only arenas are closed by the organizer")?;
+        if arena.ends_at.is_some_and(|ends_at| now < ends_at) {
+            return Err("Arena is still running".to_string());
+        }
+        arena.waiting.clear();
+        self.status = TournamentStatus::Finished;
+        Ok(())
+    }
+
+    fn advance_arena(&mut self, now: u64) -> Result<Option<&TournamentRound>, String> {
+        if self.rounds.is_empty() && self.players.len() < 2 {
+            return Err("Not enough players".to_string());
+        }
+        let players: Vec<String> = self.players.iter().map(|p| p.chain_id.clone()).collect();
+        let arena = self.arena.as_mut().expect("Arena state missing");
+        let ends_at = match arena.ends_at {
+            Some(ends_at) => ends_at,
+            None => {
+                let ends_at = now + arena.duration_secs * 1_000_000;
+                arena.ends_at = Some(ends_at);
+                arena.waiting = players;
+                self.status = TournamentStatus::InProgress;
+                ends_at
+            }
+        };
+
+        if now >= ends_at {
+            arena.waiting.clear();
+            let games_running = self
+                .rounds
+                .iter()
+                .any(|r| !r.is_complete());
+            if !games_running {
+                self.status = TournamentStatus::Finished;
+            }
+            return Ok(None);
+        }
+
+        // Highest scores meet first; avoid an immediate rematch when someone else is waiting
+        let mut waiting = std::mem::take(&mut arena.waiting);
+        waiting.sort_by_key(|chain_id| std::cmp::Reverse(arena.points(chain_id)));
+        let mut pairs = Vec::new();
+        while waiting.len() >= 2 {
+            let first = waiting.remove(0);
+            let last_opponent = self
+                .rounds
+                .iter()
+                .rev()
+                .flat_map(|r| r.pairings.iter())
+                .find_map(|p| p.opponent_of(&first).map(str::to_string));
+            let index = if waiting.len() > 1 && last_opponent.as_deref() == Some(waiting[0].as_str()) {
+                1
+            } else {
+                0
+            };
+            let second = waiting.remove(index);
+            pairs.push((first, second));
+        }
+        arena.waiting = waiting;
+
+        if pairs.is_empty() {
+            return Ok(None);
+        }
+        let number = self.rounds.len() as u32 + 1;
+        let pairings = pairs
+            .into_iter()
+            .enumerate()
+            .map(|(i, (a, b))| {
+                // Whoever has had White less often gets it
+                let (white, black) = self.assign_colors(&a, &b);
+                Pairing {
+                    board: i as u32 + 1,
+                    white_chain_id: white,
+                    black_chain_id: Some(black),
+                    match_id: None,
+                    result: None,
+                    bracket: None,
+                    series: None,
+                }
+            })
+            .collect();
+        self.rounds.push(TournamentRound { number, pairings });
+        Ok(self.rounds.last())
+    }
+
+    // Circle (Berger) method; an odd field gets a rotating bye
+    fn round_robin_pairs(&self, number: u32) -> Vec<(String, Option<String>)> {
+        let mut ids: Vec<Option<String>> = self.players.iter().map(|p| Some(p.chain_id.clone())).collect();
+        if ids.len() % 2 == 1 {
+            ids.push(None);
+        }
+        let n = ids.len();
+        let r = (number as usize - 1) % (n - 1);
+
+        // Rotate everyone but the first entry r steps
+        let mut order = vec![ids[0].clone()];
+        for i in 0..n - 1 {
+            order.push(ids[1 + (i + n - 1 - r) % (n - 1)].clone());
+        }
+
+        let mut pairs = Vec::new();
+        for i in 0..n / 2 {
+            let (mut a, mut b) = (order[i].clone(), order[n - 1 - i].clone());
+            // Alternate colours of the fixed player between rounds
+            if (i == 0 && number % 2 != 1) || (i > 0 && i % 2 == 1) {
+                std::mem::swap(&mut a, &mut b);
+            }
+            match (a, b) {
+                (Some(white), Some(black)) => pairs.push((white, Some(black))),
+                (Some(player), None) | (None, Some(player)) => pairs.push((player, None)),
+                (None, None) => {}
+            }
+        }
+        // Real games first, byes last
+        pairs.sort_by_key(|(_, black)| black.is_none());
+        pairs
+    }
+
+    // Dutch-style Swiss: rank by score, pair the top half of each score group against the
+    // bottom half, avoiding rematches by backtracking and floating players down when needed
+    fn swiss_pairs(&self) -> Vec<(String, Option<String>)> {
+        let mut ranked: Vec<&TournamentPlayer> = self.players.iter().collect();
+        ranked.sort_by_key(|p| (std::cmp::Reverse(self.half_points(&p.chain_id)), p.seed));
+
+        let mut bye = None;
+        if ranked.len() % 2 == 1 {
+            // Lowest ranked player who has not had a bye yet
+            let index = ranked
+                .iter()
+                .rposition(|p| !self.had_bye(&p.chain_id))
+                .unwrap_or(ranked.len() - 1);
+            bye = Some(ranked.remove(index).chain_id.clone());
+        }
+
+        let ids: Vec<String> = ranked.iter().map(|p| p.chain_id.clone()).collect();
+        let mut used = vec![false; ids.len()];
+        let mut pairs = Vec::new();
+        if !self.pair_dutch(&ids, &mut used, &mut pairs, true) {
+            // Every arrangement needs a rematch; allow them rather than stall the event
+            used = vec![false; ids.len()];
+            pairs.clear();
+            self.pair_dutch(&ids, &mut used, &mut pairs, false);
+        }
+
+        let mut result: Vec<(String, Option<String>)> = pairs
+            .into_iter()
+            .map(|(a, b)| {
+                let (white, black) = self.assign_colors(&ids[a], &ids[b]);
+                (white, Some(black))
+            })
+            .collect();
+        if let Some(bye) = bye {
+            result.push((bye, None));
+        }
+        result
+    }
+
+    fn pair_dutch(
+        &self,
+        ids: &[String],
+        used: &mut Vec<bool>,
+        pairs: &mut Vec<(usize, usize)>,
+        avoid_rematches: bool,
+    ) -> bool {
+        let first = match used.iter().position(|u| !u) {
+            Some(first) => first,
+            None => return true,
+        };
+        used[first] = true;
+
+        // Preferred opponent is the matching player in the bottom half of the score group
+        let score = self.half_points(&ids[first]);
+        let group: Vec<usize> = (first + 1..ids.len())
+            .filter(|&i| !used[i] && self.half_points(&ids[i]) == score)
+            .collect();
+        let offset = group.len() / 2;
+        let mut candidates: Vec<usize> = group[offset.min(group.len())..].to_vec();
+        candidates.extend_from_slice(&group[..offset.min(group.len())]);
+        candidates.extend((first + 1..ids.len()).filter(|&i| !used[i] && self.half_points(&ids[i]) != score));
+
+        for candidate in candidates {
+            if avoid_rematches && self.have_played(&ids[first], &ids[candidate]) {
+                continue;
+            }
+            used[candidate] = true;
+            pairs.push((first, candidate));
+            if self.pair_dutch(ids, used, pairs, avoid_rematches) {
+                return true;
+            }
+            pairs.pop();
+            used[candidate] = false;
+        }
+
+        used[first] = false;
+        false
+    }
+
+    // Give White to whoever has had it less; the higher ranked player breaks ties by alternating
+    fn assign_colors(&self, higher: &str, lower: &str) -> (String, String) {
+        let balance = |chain_id: &str| -> i32 {
+            self.rounds
+                .iter()
+                .flat_map(|r| r.pairings.iter())
+                .filter(|p| !p.is_bye())
+                .map(|p| {
+                    if p.white_chain_id == chain_id {
+                        1
+                    } else if p.black_chain_id.as_deref() == Some(chain_id) {
+                        -1
+                    } else {
+                        0
+                    }
+                })
+                .sum()
+        };
+        let (h, l) = (balance(higher), balance(lower));
+        let higher_white = if h != l { h < l } else { h <= 0 };
+        if higher_white {
+            (higher.to_string(), lower.to_string())
+        } else {
+            (lower.to_string(), higher.to_string())
+        }
+    }
+
+    // Players still alive in a knockout, in bracket order
+    fn knockout_survivors(&self) -> Vec<String> {
+        match self.current_round() {
+            None => self.players.iter().map(|p| p.chain_id.clone()).collect(),
+            Some(round) => round
+                .pairings
+                .iter()
+                .filter_map(|p| p.advancing_chain_id().map(str::to_string))
+                .collect(),
+        }
+    }
+
+    // First round uses standard bracket seeding (1 v N, then 4 v 5, ...) with byes for the
+    // top seeds; later rounds pair the winners of neighbouring boards
+    fn knockout_pairs(&self) -> Vec<(String, Option<String>)> {
+        let survivors = self.knockout_survivors();
+        if !self.rounds.is_empty() {
+            return survivors
+                .chunks(2)
+                .map(|pair| (pair[0].clone(), pair.get(1).cloned()))
+                .collect();
+        }
+
+        let size = survivors.len().next_power_of_two();
+        let mut order = vec![1usize];
+        while order.len() < size {
+            let k = order.len() * 2;
+            order = order.iter().flat_map(|&s| [s, k + 1 - s]).collect();
+        }
+        order
+            .chunks(2)
+            .map(|pair| {
+                let white = survivors[pair[0] - 1].clone();
+                let black = survivors.get(pair[1] - 1).cloned();
+                (white, black)
+            })
+            .collect()
+    }
+
+    // Knockout losses so far (byes never count)
+    fn losses(&self, chain_id: &str) -> u32 {
+        self.rounds
+            .iter()
+            .flat_map(|r| r.pairings.iter())
+            .filter(|p| !p.is_bye() && p.opponent_of(chain_id).is_some())
+            .filter(|p| p.result.is_some() && p.advancing_chain_id() != Some(chain_id))
+            .count() as u32
+    }
+
+    // Double elimination: everyone with fewer than two losses, in seed order
+    fn alive_players(&self) -> Vec<String> {
+        self.players
+            .iter()
+            .map(|p| p.chain_id.clone())
+            .filter(|chain_id| self.losses(chain_id) < 2)
+            .collect()
+    }
+
+    // Unbeaten players meet in the winners' bracket, once-beaten players in the losers'
+    // bracket, and the last unbeaten player meets the last once-beaten one in the final.
+    // A reset final happens naturally: if the unbeaten player loses, both have one loss.
+    fn double_elimination_pairs(&self) -> Vec<(String, Option<String>, Option<BracketSide>)> {
+        if self.rounds.is_empty() {
+            return self
+                .knockout_pairs()
+                .into_iter()
+                .map(|(w, b)| (w, b, Some(BracketSide::Winners)))
+                .collect();
+        }
+
+        // Keep bracket order: by the board of each player's most recent game
+        let last_board = |chain_id: &str| -> (u32, u32) {
+            self.rounds
+                .iter()
+                .rev()
+                .find_map(|r| {
+                    r.pairings
+                        .iter()
+                        .find(|p| p.white_chain_id == chain_id || p.black_chain_id.as_deref() == Some(chain_id))
+                        .map(|p| (r.number, p.board))
+                })
+                .unwrap_or((0, 0))
+        };
+        let mut unbeaten: Vec<String> = self.alive_players().into_iter().filter(|c| self.losses(c) == 0).collect();
+        let mut once_beaten: Vec<String> = self.alive_players().into_iter().filter(|c| self.losses(c) == 1).collect();
+        unbeaten.sort_by_key(|c| last_board(c).1);
+        once_beaten.sort_by_key(|c| last_board(c));
+
+        if unbeaten.len() == 1 && once_beaten.len() == 1 {
+            return vec![(unbeaten.remove(0), Some(once_beaten.remove(0)), Some(BracketSide::GrandFinal))];
+        }
+        if unbeaten.is_empty() && once_beaten.len() == 2 {
+            // Reset final after the unbeaten player lost the first one
+            let black = once_beaten.pop();
+            return vec![(once_beaten.remove(0), black, Some(BracketSide::GrandFinal))];
+        }
+
+        let mut pairs = Vec::new();
+        if unbeaten.len() >= 2 {
+            pairs.extend(
+                unbeaten
+                    .chunks(2)
+                    .map(|pair| (pair[0].clone(), pair.get(1).cloned(), Some(BracketSide::Winners))),
+            );
+        }
+        if once_beaten.len() >= 2 {
+            // The longest-waiting player takes the bye when the count is odd
+            let mut queue = once_beaten;
+            if queue.len() % 2 == 1 {
+                pairs.push((queue.remove(0), None, Some(BracketSide::Losers)));
+            }
+            pairs.extend(
+                queue
+                    .chunks(2)
+                    .map(|pair| (pair[0].clone(), pair.get(1).cloned(), Some(BracketSide::Losers))),
+            );
+        }
+        // Real games first, byes last
+        pairs.sort_by_key(|(_, black, _)| black.is_none());
+        pairs
+    }
+
+    // Bracket view for knockout formats
+    pub fn bracket(&self) -> Bracket {
+        let rounds = self
+            .rounds
+            .iter()
+            .flat_map(|round| {
+                [BracketSide::Winners, BracketSide::Losers, BracketSide::GrandFinal]
+                    .into_iter()
+                    .filter_map(move |side| {
+                        let matches: Vec<BracketMatch> = round
+                            .pairings
+                            .iter()
+                            .filter(|p| p.bracket == Some(side))
+                            .map(|p| {
+                                let (white_score, black_score) = match (&p.series, &p.black_chain_id) {
+                                    (Some(series), Some(black)) => (
+                                        series.half_points(&p.white_chain_id) as f64 / 2.0,
+                                        series.half_points(black) as f64 / 2.0,
+                                    ),
+                                    _ => (
+                                        p.half_points_for(&p.white_chain_id).unwrap_or(0) as f64 / 2.0,
+                                        p.black_chain_id
+                                            .as_deref()
+                                            .and_then(|b| p.half_points_for(b))
+                                            .unwrap_or(0) as f64
+                                            / 2.0,
+                                    ),
+                                };
+                                let match_ids = match &p.series {
+                                    Some(series) => series.results.iter().map(|r| r.match_id.clone()).collect(),
+                                    None => p.match_id.iter().cloned().collect(),
+                                };
+                                BracketMatch {
+                                    board: p.board,
+                                    white_chain_id: p.white_chain_id.clone(),
+                                    black_chain_id: p.black_chain_id.clone(),
+                                    white_score,
+                                    black_score,
+                                    winner_chain_id: p
+                                        .result
+                                        .and_then(|_| p.advancing_chain_id())
+                                        .or_else(|| p.is_bye().then_some(p.white_chain_id.as_str()))
+                                        .map(str::to_string),
+                                    match_ids,
+                                }
+                            })
+                            .collect();
+                        (!matches.is_empty()).then_some(BracketRound {
+                            round: round.number,
+                            side,
+                            matches,
+                        })
+                    })
+            })
+            .collect();
+
+        let champion_chain_id = if self.status == TournamentStatus::Finished {
+            match self.format {
+                TournamentFormat::SingleElimination => self.knockout_survivors().first().cloned(),
+                TournamentFormat::DoubleElimination => self.alive_players().first().cloned(),
+                _ => None,
+            }
+        } else {
+            None
+        };
+        Bracket {
+            tournament_id: self.tournament_id.clone(),
+            format: self.format,
+            rounds,
+            champion_chain_id,
+        }
+    }
+
+    fn had_bye(&self, chain_id: &str) -> bool {
+        self.rounds
+            .iter()
+            .flat_map(|r| r.pairings.iter())
+            .any(|p| p.is_bye() && p.white_chain_id == chain_id)
+    }
+
+    fn have_played(&self, a: &str, b: &str) -> bool {
+        self.rounds
+            .iter()
+            .flat_map(|r| r.pairings.iter())
+            .any(|p| p.opponent_of(a) == Some(b))
+    }
+
+    pub fn half_points(&self, chain_id: &str) -> u32 {
+        self.rounds
+            .iter()
+            .flat_map(|r| r.pairings.iter())
+            .filter_map(|p| p.half_points_for(chain_id))
+            .sum()
+    }
+
+    // Standings with Buchholz (sum of opponents' scores) and Sonneborn-Berger
+    // (scores of beaten opponents plus half the scores of drawn ones)
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<(Standing, u32)> = self
+            .players
+            .iter()
+            .map(|player| {
+                let mut buchholz = 0;
+                let mut sonneborn_berger = 0;
+                for pairing in self.rounds.iter().flat_map(|r| r.pairings.iter()) {
+                    let (opponent, scored) = match (
+                        pairing.opponent_of(&player.chain_id),
+                        pairing.half_points_for(&player.chain_id),
+                    ) {
+                        (Some(opponent), Some(scored)) => (opponent, scored),
+                        _ => continue,
+                    };
+                    let opponent_points = self.half_points(opponent);
+                    buchholz += opponent_points;
+                    sonneborn_berger += opponent_points * scored;
+                }
+                let points = match self.arena.as_ref() {
+                    Some(arena) => arena.points(&player.chain_id) as f64,
+                    None => self.half_points(&player.chain_id) as f64 / 2.0,
+                };
+                let standing = Standing {
+                    rank: 0,
+                    chain_id: player.chain_id.clone(),
+                    name: player.name.clone(),
+                    points,
+                    buchholz: buchholz as f64 / 2.0,
+                    sonneborn_berger: sonneborn_berger as f64 / 4.0,
+                };
+                (standing, player.seed)
+            })
+            .collect();
+
+        standings.sort_by(|(a, seed_a), (b, seed_b)| {
+            b.points
+                .total_cmp(&a.points)
+                .then(b.buchholz.total_cmp(&a.buchholz))
+                .then(b.sonneborn_berger.total_cmp(&a.sonneborn_berger))
+                .then(seed_a.cmp(seed_b))
+        });
+        standings
+            .into_iter()
+            .enumerate()
+            .map(|(i, (mut standing, _))| {
+                standing.rank = i as u32 + 1;
+                standing
+            })
+            .collect()
+    }
+
+    // Players by final place: the standings, with the bracket's champion first in knockouts
+    pub fn final_ranking(&self) -> Vec<&TournamentPlayer> {
+        let mut ranking: Vec<&TournamentPlayer> = self
+            .standings()
+            .iter()
+            .filter_map(|standing| self.player(&standing.chain_id))
+            .collect();
+        if Self::is_knockout_format(self.format) {
+            if let Some(champion) = self.bracket().champion_chain_id {
+                if let Some(index) = ranking.iter().position(|p| p.chain_id == champion) {
+                    let champion = ranking.remove(index);
+                    ranking.insert(0, champion);
+                }
+            }
+        }
+        ranking
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn tournament(format: TournamentFormat, players: usize, rounds: u32) -> Tournament {
+        let mut tournament = Tournament::new(
+            "t".to_string(),
+            "Test".to_string(),
+            format,
+            "organizer".to_string(),
+            rounds,
+            None,
+            0,
+            None,
+        );
+        for i in 1..=players {
+            tournament.register(format!("p{}", i), format!("Player {}", i), None).unwrap();
+        }
+        tournament
+    }
+
+    fn seed(chain_id: &str) -> u32 {
+        chain_id[1..].parse().unwrap()
+    }
+
+    // Plays out every game of a board, each won by `winner` (None for draws)
+    fn decide(tournament: &mut Tournament, round: u32, board: u32, winner: Option<&str>) {
+        while let Some(game) = tournament.next_game(round, board) {
+            let match_id = format!("{}-{}-{}", round, board, game.game_number);
+            let winner = winner.map(str::to_string);
+            tournament.record_game(round, board, game.game_number, match_id, winner, false, false);
+        }
+    }
+
+    // Runs the tournament to the end, letting `winner` pick the result of every board;
+    // returns the pairings of each round
+    fn play_out(
+        tournament: &mut Tournament,
+        winner: impl Fn(u32, &Pairing) -> Option<String>,
+    ) -> Vec<Vec<Pairing>> {
+        let mut rounds = Vec::new();
+        while let Some(round) = tournament.advance(0).unwrap() {
+            let number = round.number;
+            let pairings = round.pairings.clone();
+            for pairing in pairings.iter().filter(|p| !p.is_bye()) {
+                let winner = winner(number, pairing);
+                decide(tournament, number, pairing.board, winner.as_deref());
+            }
+            rounds.push(pairings);
+        }
+        rounds
+    }
+
+    fn higher_seed(pairing: &Pairing) -> Option<String> {
+        let black = pairing.black_chain_id.as_deref()?;
+        let winner = if seed(&pairing.white_chain_id) < seed(black) {
+            &pairing.white_chain_id
+        } else {
+            black
+        };
+        Some(winner.to_string())
+    }
+
+    fn games(pairings: &[Pairing]) -> Vec<(String, String)> {
+        pairings
+            .iter()
+            .filter_map(|p| Some((p.white_chain_id.clone(), p.black_chain_id.clone()?)))
+            .collect()
+    }
+
+    fn byes(pairings: &[Pairing]) -> Vec<String> {
+        pairings.iter().filter(|p| p.is_bye()).map(|p| p.white_chain_id.clone()).collect()
+    }
+
+    #[test]
+    fn round_robin_pairs_everyone_once() {
+        for players in [4, 5, 6] {
+            let mut tournament = tournament(TournamentFormat::RoundRobin, players, 0);
+            let rounds = play_out(&mut tournament, |_, _| None);
+            let expected_rounds = if players % 2 == 1 { players } else { players - 1 };
+            assert_eq!(rounds.len(), expected_rounds);
+            assert_eq!(tournament.status, TournamentStatus::Finished);
+
+            let mut met = HashSet::new();
+            for (white, black) in rounds.iter().flat_map(|r| games(r)) {
+                let pair = if white < black { (white, black) } else { (black, white) };
+                assert!(met.insert(pair), "rematch in a round robin");
+            }
+            assert_eq!(met.len(), players * (players - 1) / 2);
+
+            // An odd field gives every player exactly one bye
+            let byes: Vec<String> = rounds.iter().flat_map(|r| byes(r)).collect();
+            let expected_byes = if players % 2 == 1 { players } else { 0 };
+            assert_eq!(byes.len(), expected_byes);
+            assert_eq!(byes.iter().collect::<HashSet<_>>().len(), expected_byes);
+        }
+    }
+
+    #[test]
+    fn round_robin_balances_colours() {
+        let mut tournament = tournament(TournamentFormat::RoundRobin, 6, 0);
+        let rounds = play_out(&mut tournament, |_, _| None);
+        for player in &tournament.players {
+            let whites = rounds
+                .iter()
+                .flat_map(|r| games(r))
+                .filter(|(white, _)| *white == player.chain_id)
+                .count();
+            assert!((2..=3).contains(&whites), "{} had White {} times", player.chain_id, whites);
+        }
+    }
+
+    #[test]
+    fn swiss_first_round_pairs_top_half_against_bottom_half() {
+        let mut tournament = tournament(TournamentFormat::Swiss, 6, 3);
+        let round = tournament.advance(0).unwrap().unwrap();
+        let expected = [("p1", "p4"), ("p2", "p5"), ("p3", "p6")];
+        let expected: Vec<(String, String)> =
+            expected.iter().map(|(w, b)| (w.to_string(), b.to_string())).collect();
+        assert_eq!(games(&round.pairings), expected);
+    }
+
+    #[test]
+    fn swiss_avoids_rematches_and_repeated_byes() {
+        let mut tournament = tournament(TournamentFormat::Swiss, 7, 4);
+        let rounds = play_out(&mut tournament, |_, pairing| higher_seed(pairing));
+        assert_eq!(rounds.len(), 4);
+        assert_eq!(tournament.status, TournamentStatus::Finished);
+
+        let mut met = HashSet::new();
+        for (white, black) in rounds.iter().flat_map(|r| games(r)) {
+            let pair = if white < black { (white, black) } else { (black, white) };
+            assert!(met.insert(pair), "rematch in a Swiss");
+        }
+        let byes: Vec<String> = rounds.iter().flat_map(|r| byes(r)).collect();
+        assert_eq!(byes.len(), 4);
+        assert_eq!(byes.iter().collect::<HashSet<_>>().len(), 4);
+        // The leader wins every game and tops the standings
+        assert_eq!(tournament.standings()[0].chain_id, "p1");
+        assert_eq!(tournament.standings()[0].points, 4.0);
+    }
+
+    #[test]
+    fn single_elimination_seeds_the_bracket_with_byes_for_top_seeds() {
+        let mut tournament = tournament(TournamentFormat::SingleElimination, 6, 0);
+        let rounds = play_out(&mut tournament, |_, pairing| higher_seed(pairing));
+        assert_eq!(rounds.len(), 3);
+
+        let to_strings = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs.iter().map(|(w, b)| (w.to_string(), b.to_string())).collect()
+        };
+        assert_eq!(byes(&rounds[0]), vec!["p1", "p2"]);
+        assert_eq!(games(&rounds[0]), to_strings(&[("p4", "p5"), ("p3", "p6")]));
+        assert_eq!(games(&rounds[1]), to_strings(&[("p1", "p4"), ("p2", "p3")]));
+        assert_eq!(games(&rounds[2]), to_strings(&[("p1", "p2")]));
+
+        assert_eq!(tournament.status, TournamentStatus::Finished);
+        assert_eq!(tournament.bracket().champion_chain_id.as_deref(), Some("p1"));
+        assert_eq!(tournament.final_ranking()[0].chain_id, "p1");
+    }
+
+    #[test]
+    fn double_elimination_plays_a_reset_final() {
+        let mut tournament = tournament(TournamentFormat::DoubleElimination, 4, 0);
+        // Higher seeds win, except that p2 takes the first grand final from p1
+        let rounds = play_out(&mut tournament, |round, pairing| {
+            if round == 4 {
+                Some("p2".to_string())
+            } else {
+                higher_seed(pairing)
+            }
+        });
+        let sides: Vec<Vec<BracketSide>> = rounds
+            .iter()
+            .map(|r| r.iter().filter_map(|p| p.bracket).collect())
+            .collect();
+        assert_eq!(
+            sides,
+            vec![
+                vec![BracketSide::Winners, BracketSide::Winners],
+                vec![BracketSide::Winners, BracketSide::Losers],
+                vec![BracketSide::Losers],
+                vec![BracketSide::GrandFinal],
+                vec![BracketSide::GrandFinal],
+            ]
+        );
+        assert_eq!(tournament.status, TournamentStatus::Finished);
+        assert_eq!(tournament.bracket().champion_chain_id.as_deref(), Some("p1"));
+        for player in ["p2", "p3", "p4"] {
+            assert_eq!(tournament.losses(player), 2);
+        }
+        assert_eq!(tournament.losses("p1"), 1);
+    }
+
+    #[test]
+    fn knockout_mini_matches_always_end_in_armageddon() {
+        let without_armageddon = SeriesFormat {
+            kind: SeriesKind::BestOf,
+            target: 2,
+            armageddon_tiebreak: false,
+            tiebreak_games: None,
+        };
+        for format in [TournamentFormat::SingleElimination, TournamentFormat::DoubleElimination] {
+            let tournament = Tournament::new(
+                "t".to_string(),
+                "Test".to_string(),
+                format,
+                "organizer".to_string(),
+                0,
+                None,
+                0,
+                Some(without_armageddon),
+            );
+            assert!(tournament.mini_match.unwrap().armageddon_tiebreak);
+        }
+        let swiss = Tournament::new(
+            "t".to_string(),
+            "Test".to_string(),
+            TournamentFormat::Swiss,
+            "organizer".to_string(),
+            0,
+            None,
+            0,
+            None,
+        );
+        assert!(swiss.mini_match.is_none());
+    }
+
+    #[test]
+    fn drawn_mini_match_goes_to_tiebreaks_then_armageddon() {
+        let mut tournament = tournament(TournamentFormat::SingleElimination, 2, 0);
+        tournament.advance(0).unwrap();
+
+        let mut phases = Vec::new();
+        while let Some(game) = tournament.next_game(1, 1) {
+            phases.push((game.time_control, game.armageddon));
+            let record = tournament.record_game(1, 1, game.game_number, game.game_number.to_string(), None, false, false);
+            if game.armageddon {
+                assert_eq!(record, GameRecord::PairingDone);
+                // Draw odds: the drawn Armageddon game goes to Black
+                assert_eq!(game.black_chain_id, "p2");
+            } else {
+                assert_eq!(record, GameRecord::NextGame);
+            }
+        }
+        assert_eq!(
+            phases,
+            vec![
+                (None, false),
+                (None, false),
+                (Some(TIEBREAK_TIME_CONTROL), false),
+                (Some(TIEBREAK_TIME_CONTROL), false),
+                (Some(ARMAGEDDON_TIME_CONTROL), true),
+            ]
+        );
+        assert_eq!(tournament.pairing(1, 1).unwrap().result, Some(PairingResult::BlackWins));
+        assert!(tournament.advance(0).unwrap().is_none());
+        assert_eq!(tournament.bracket().champion_chain_id.as_deref(), Some("p2"));
+    }
+
+    fn ranking(players: usize) -> Vec<TournamentPlayer> {
+        (1..=players)
+            .map(|i| TournamentPlayer {
+                chain_id: format!("p{}", i),
+                name: format!("Player {}", i),
+                seed: i as u32,
+                owner: None,
+            })
+            .collect()
+    }
+
+    fn deposit(kind: DepositKind, amount: u128) -> PoolDeposit {
+        PoolDeposit {
+            kind,
+            chain_id: "sponsor".to_string(),
+            owner: AccountOwner::CHAIN,
+            amount: Amount::from_attos(amount),
+            timestamp: "0".to_string(),
+        }
+    }
+
+    #[test]
+    fn prize_pool_rejects_invalid_payouts() {
+        assert!(PrizePool::new(None, Some(vec![60, 30])).is_err());
+        assert!(PrizePool::new(None, Some(vec![100, 0])).is_err());
+        assert!(PrizePool::new(None, Some(Vec::new())).is_err());
+        assert!(PrizePool::new(None, Some(vec![10; MAX_PAID_PLACES + 1])).is_err());
+        let pool = PrizePool::new(Some(Amount::ZERO), None).unwrap();
+        assert_eq!(pool.entry_fee, None);
+        assert_eq!(pool.payout_percentages, DEFAULT_PAYOUT_PERCENTAGES.to_vec());
+    }
+
+    #[test]
+    fn prize_pool_pays_out_everything_after_the_fee() {
+        let mut pool = PrizePool::new(None, None).unwrap();
+        pool.add_deposit(deposit(DepositKind::EntryFee, 1_000));
+        pool.add_deposit(deposit(DepositKind::Sponsorship, 101));
+        pool.platform_fee = Amount::from_attos(100);
+        assert_eq!(pool.total(), Amount::from_attos(1_101));
+
+        let players = ranking(4);
+        let payouts = pool.compute_payouts(&players.iter().collect::<Vec<_>>());
+        let amounts: Vec<u128> = payouts.iter().map(|p| u128::from(p.amount)).collect();
+        // 1001 split 50/30/20, the rounding leftover going to the winner
+        assert_eq!(amounts, vec![501, 300, 200]);
+        assert_eq!(payouts.iter().map(|p| p.rank).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(payouts[0].chain_id, "p1");
+    }
+
+    #[test]
+    fn prize_pool_gives_unfilled_places_to_the_winner() {
+        let mut pool = PrizePool::new(None, None).unwrap();
+        pool.add_deposit(deposit(DepositKind::Sponsorship, 1_000));
+        let players = ranking(2);
+        let payouts = pool.compute_payouts(&players.iter().collect::<Vec<_>>());
+        let amounts: Vec<u128> = payouts.iter().map(|p| u128::from(p.amount)).collect();
+        assert_eq!(amounts, vec![700, 300]);
+    }
+}
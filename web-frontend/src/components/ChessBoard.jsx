@@ -241,6 +241,7 @@ const ChessBoard = ({ game, playerColor, onMove, isPlayerTurn }) => {
   const formatGameStatus = (status) => {
     const statusMap = {
       WaitingForPlayer: 'Waiting for player',
+      WaitingForReady: 'Waiting for players to get ready',
       Active: 'In progress',
       Ended: 'Game ended',
     };
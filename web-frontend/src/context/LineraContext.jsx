@@ -517,6 +517,11 @@ const LineraContextProvider = ({ children }) => {
     await refresh();
   }, [gql, refresh]);
 
+  const setReady = useCallback(async () => {
+    await gql(`mutation { setReady }`);
+    await refresh();
+  }, [gql, refresh]);
+
   const endGame = useCallback(
     async (status) => {
       await gql(`mutation { endGame(status: ${status}) }`);
@@ -550,6 +555,7 @@ const LineraContextProvider = ({ children }) => {
       joinMatch,
       makeMove,
       resignMatch,
+      setReady,
       endGame,
     }),
     [
@@ -572,6 +578,7 @@ const LineraContextProvider = ({ children }) => {
       ready,
       refresh,
       resignMatch,
+      setReady,
       syncHeight,
       syncUnlocked,
       myReady,
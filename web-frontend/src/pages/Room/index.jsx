@@ -25,6 +25,9 @@ const Room = () => {
     makeMove,
     joinMatch,
     resignMatch,
+    setReady,
+    myReady,
+    opponentReady,
   } = useContext(LineraContext);
   const { showToast } = useToast();
   const hasJoinedRef = useRef(false);
@@ -93,6 +96,15 @@ const Room = () => {
     }
   };
 
+  const handleReady = async () => {
+    try {
+      await setReady();
+    } catch (error) {
+      console.error("Error confirming ready:", error);
+      showToast(`Failed to confirm ready: ${error?.message || error}`, "error");
+    }
+  };
+
   const getPlayerColor = () => {
     if (!game || !chainId) return null;
     if (isHost) return "White";
@@ -171,6 +183,15 @@ const Room = () => {
               <div className={styles.status}>
                 Status: <span className={styles.statusValue}>{game.status}</span>
               </div>
+              {game.status === "WaitingForReady" && (
+                <div className={styles.turnIndicator}>
+                  {myReady
+                    ? opponentReady
+                      ? "Starting..."
+                      : "⏳ Waiting for opponent to get ready"
+                    : "Both players must confirm before the game starts"}
+                </div>
+              )}
               {game.status === "Active" && (
                 <div className={styles.turnIndicator}>
                   {isPlayerTurn() ? "✅ Your turn - Click or drag pieces!" : "⏳ Opponent's turn"}
@@ -179,6 +200,11 @@ const Room = () => {
             </>
           )}
         </div>
+        {game && game.status === "WaitingForReady" && !myReady && (
+          <button className={styles.resignButton} onClick={handleReady}>
+            Ready
+          </button>
+        )}
         {game && game.status === "Active" && (
           <button className={styles.resignButton} onClick={handleResign}>
             Resign
@@ -70,6 +70,7 @@ export const chessMoveToUci = (move) => {
 export const formatGameStatus = (status) => {
   const statusMap = {
     WaitingForPlayer: 'Waiting for player',
+    WaitingForReady: 'Waiting for players to get ready',
     InProgress: 'In progress',
     WhiteWon: 'White won',
     BlackWon: 'Black won',